@@ -26,14 +26,22 @@ mkdir $test_dir
 cd $test_dir
 
 # generation
+$cwd/target/release/cargo-embassy embassy init test-esp32 --chip esp32
+$cwd/target/release/cargo-embassy embassy init test-esp32c2 --chip esp32c2
 $cwd/target/release/cargo-embassy embassy init test-esp32c3 --chip esp32c3
+$cwd/target/release/cargo-embassy embassy init test-esp32c6 --chip esp32c6
+$cwd/target/release/cargo-embassy embassy init test-esp32h2 --chip esp32h2
 $cwd/target/release/cargo-embassy embassy init test-esp32s2 --chip esp32s2
 $cwd/target/release/cargo-embassy embassy init test-esp32s3 --chip esp32s3
 
 . $HOME/export-esp.sh
 
 # compile
+cd $test_dir/test-esp32; cargo build --release
+cd $test_dir/test-esp32c2; cargo build --release
 cd $test_dir/test-esp32c3; cargo build --release
+cd $test_dir/test-esp32c6; cargo build --release
+cd $test_dir/test-esp32h2; cargo build --release
 cd $test_dir/test-esp32s2; cargo build --release
 cd $test_dir/test-esp32s3; cargo build --release
 
@@ -26,12 +26,24 @@ $cwd/target/release/cargo-embassy embassy init test-stm32g4 --chip stm32g431rb -
 # nrf
 $cwd/target/release/cargo-embassy embassy init test-nrf52840 --chip nrf52840
 $cwd/target/release/cargo-embassy embassy init test-nrf52832 --chip nrf52832-xxab --softdevice s132
+$cwd/target/release/cargo-embassy embassy init test-nrf5340 --chip nrf5340-app-s
+$cwd/target/release/cargo-embassy embassy init test-nrf9160 --chip nrf9160-ns
+$cwd/target/release/cargo-embassy embassy init test-nrf54l15 --chip nrf54l15
+
+# rp
+$cwd/target/release/cargo-embassy embassy init test-rp2040 --chip rp2040
+$cwd/target/release/cargo-embassy embassy init test-rp235x --chip rp2350a
 
 # compile
 cd test-stm32g0; cargo build; cargo build --no-default-features --release
 cd ../test-stm32g4; cargo build; cargo build --no-default-features --release
 cd ../test-nrf52840; cargo build; cargo build --no-default-features --release
 cd ../test-nrf52832; cargo build; cargo build --no-default-features --release
+cd ../test-nrf5340; cargo build; cargo build --no-default-features --release
+cd ../test-nrf9160; cargo build; cargo build --no-default-features --release
+cd ../test-nrf54l15; cargo build; cargo build --no-default-features --release
+cd ../test-rp2040; cargo build; cargo build --no-default-features --release
+cd ../test-rp235x; cargo build; cargo build --no-default-features --release
 
 # clean up
 cd $cwd
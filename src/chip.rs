@@ -1,78 +1,239 @@
 pub mod family;
+pub mod memory;
+pub mod search;
 pub mod target;
 
-use family::esp::Variant;
+use family::{esp::Variant, rp::Variant as RpVariant};
 
 use crate::error::{Error, InvalidChip};
-use std::str::FromStr;
+use memory::Memory;
+use probe_rs::{config::families, CoreType};
+use strsim::damerau_levenshtein;
+use target::Target;
 
 pub(crate) struct Chip {
     pub family: family::Family,
-    pub target: target::Target,
+    pub target: Target,
     pub name: String,
 }
 
-impl FromStr for Chip {
-    type Err = Error;
-
-    fn from_str(chip: &str) -> Result<Self, Self::Err> {
-        use family::{mem_region::MemRegion, Family::*};
-        use target::Target::*;
-
-        let chips = [
-            // nRF
-            ("nrf52805", (NRF(MemRegion::NRF52805), Thumbv7f)),
-            ("nrf52810", (NRF(MemRegion::NRF52810), Thumbv7f)),
-            ("nrf52811", (NRF(MemRegion::NRF52811), Thumbv7f)),
-            ("nrf52820", (NRF(MemRegion::NRF52820), Thumbv7f)),
-            ("nrf52832_xxaa", (NRF(MemRegion::NRF52832_XXAA), Thumbv7f)),
-            ("nrf52832_xxab", (NRF(MemRegion::NRF52832_XXAB), Thumbv7f)),
-            ("nrf52833", (NRF(MemRegion::NRF52833), Thumbv7f)),
-            ("nrf52840", (NRF(MemRegion::NRF52840), Thumbv7f)),
-            // TODO: nrf53x and nrf91x
-            // STM
-            ("stm32c0", (STM, Thumbv6)),
-            ("stm32f0", (STM, Thumbv6)),
-            ("stm32f1", (STM, Thumbv7)),
-            ("stm32f2", (STM, Thumbv7)),
-            ("stm32f3", (STM, Thumbv7e)),
-            ("stm32f4", (STM, Thumbv7e)),
-            ("stm32f7", (STM, Thumbv7e)),
-            ("stm32g0", (STM, Thumbv6)),
-            ("stm32g4", (STM, Thumbv7f)),
-            ("stm32h5", (STM, Thumbv8)),
-            ("stm32h7", (STM, Thumbv7e)),
-            ("stm32l0", (STM, Thumbv6)),
-            ("stm32l1", (STM, Thumbv7)),
-            ("stm32l4", (STM, Thumbv7e)),
-            ("stm32l5", (STM, Thumbv8)),
-            ("stm32u5", (STM, Thumbv8)),
-            ("stm32wb", (STM, Thumbv7e)),
-            ("stm32wba", (STM, Thumbv8)),
-            ("stm32wl", (STM, Thumbv7e)),
-            // ESP32
-            ("esp32c3", (ESP(Variant::C3), Risc32Imc)),
-            ("esp32s2", (ESP(Variant::S2), XTensaS2)),
-            ("esp32s3", (ESP(Variant::S3), XTensaS3)),
-        ];
-
-        let (family, target) = chips
+impl Chip {
+    /// Identifies a chip by name, deriving its target from the core of its probe-rs target, if
+    /// probe-rs knows the chip.
+    pub fn new(chip: &str, core: Option<CoreType>) -> Result<Self, Error> {
+        use family::Family::*;
+
+        let (key, family) = chips()
             .iter()
-            .find_map(|(s, (f, t))| chip.starts_with(s).then(|| (f.clone(), t.clone())))
-            .ok_or(match chip {
-                "nrf52832" => Error::InvalidChip(InvalidChip::Ambiguous),
-                _ => Error::InvalidChip(InvalidChip::Unknown),
+            .find_map(|(s, f)| chip.starts_with(s).then(|| (*s, f.clone())))
+            .ok_or_else(|| match chip {
+                // not known to probe-rs, so there are no candidates from its search
+                "rp235x" | "rp2350" | "rp2354" => Error::InvalidChip(InvalidChip::Ambiguous(vec![
+                    format!("{chip}a"),
+                    format!("{chip}b"),
+                ])),
+                _ => unknown(chip),
             })?;
 
+        if AUXILIARY.contains(&chip)
+            || matches!(family, NRF(_)) && !nrf_suffix_supported(key, &chip[key.len()..])
+        {
+            return Err(unknown(chip));
+        }
+
+        let name = match &family {
+            STM => chip.to_string(),
+            NRF(_) => nrf_feature(key),
+            ESP(variant) => variant.to_string(),
+            RP(variant) => variant.to_string(),
+        };
+
+        let target = match &family {
+            // the core type does not tell the RISC-V extensions or Xtensa flavors apart
+            ESP(variant) => variant.target(),
+            _ => core
+                .or_else(|| fallback(&name).map(|(_, core)| core))
+                .and_then(|core| Target::from_core(core, has_fpu(chip)))
+                .ok_or_else(|| unknown(chip))?,
+        };
+
         Ok(Self {
-            name: match &family {
-                STM => chip.to_string(),
-                // FRAGILE: "_" is used to coerce probe-rs chip search
-                NRF(_) => chip.split('_').next().unwrap().to_string(),
-                ESP(variant) => variant.to_string(),
-            },
             family,
             target,
+            name,
+        })
+    }
+
+    /// The probe-rs target name of chips which the bundled probe-rs does not know yet.
+    pub fn fallback_probe_target(&self) -> Option<&'static str> {
+        fallback(&self.name).map(|(probe_target, _)| probe_target)
+    }
+
+    /// The memory layout for `memory.x`, `None` for families which bring their own linker
+    /// scripts. The probe-rs memory map of the RP chips covers the whole XIP window instead of
+    /// the flash of the board, so they keep their templates.
+    pub fn memory(
+        &self,
+        probe_target: Option<&probe_rs::config::Target>,
+        ram: Option<&str>,
+    ) -> Result<Option<Memory>, Error> {
+        match (&self.family, probe_target) {
+            (family::Family::NRF(Some(region)), _) => {
+                Memory::from_mem_region(region, ram).map(Some)
+            }
+            (family::Family::STM | family::Family::NRF(None), Some(probe_target)) => {
+                Memory::from_probe_target(probe_target, ram).map(Some)
+            }
+            (family::Family::STM | family::Family::NRF(None), None) => {
+                Err(Error::InvalidMemoryMap(self.name.clone()))
+            }
+            (family::Family::ESP(_) | family::Family::RP(_), _) => match ram {
+                Some(ram) => Err(Error::UnknownRamRegion(ram.into())),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Whether the chip is part of the nRF54L series, which names its peripherals differently.
+    pub fn is_nrf54l(&self) -> bool {
+        matches!(self.family, family::Family::NRF(_)) && self.name.starts_with("nrf54l")
+    }
+}
+
+/// The supported chips by the prefix of their name.
+fn chips() -> &'static [(&'static str, family::Family)] {
+    use family::{mem_region::MemRegion, Family::*};
+
+    &[
+        // nRF
+        ("nrf52805", NRF(None)),
+        ("nrf52810", NRF(None)),
+        ("nrf52811", NRF(None)),
+        ("nrf52820", NRF(None)),
+        ("nrf52832_xxaa", NRF(None)),
+        ("nrf52832_xxab", NRF(None)),
+        ("nrf52833", NRF(None)),
+        ("nrf52840", NRF(None)),
+        ("nrf5340_app_ns", NRF(Some(MemRegion::NRF5340_APP_NS))),
+        ("nrf5340", NRF(None)),
+        ("nrf9160_ns", NRF(Some(MemRegion::NRF9160_NS))),
+        ("nrf9160", NRF(Some(MemRegion::NRF9160_S))),
+        ("nrf54l15", NRF(Some(MemRegion::NRF54L15))),
+        ("nrf54l10", NRF(Some(MemRegion::NRF54L10))),
+        ("nrf54l05", NRF(Some(MemRegion::NRF54L05))),
+        // STM
+        ("stm32", STM),
+        // ESP32
+        ("esp32c2", ESP(Variant::C2)),
+        ("esp32c3", ESP(Variant::C3)),
+        ("esp32c6", ESP(Variant::C6)),
+        ("esp32h2", ESP(Variant::H2)),
+        ("esp32s2", ESP(Variant::S2)),
+        ("esp32s3", ESP(Variant::S3)),
+        // must come after the other variants as it prefixes them
+        ("esp32", ESP(Variant::ESP32)),
+        // RP
+        ("rp2040", RP(RpVariant::RP2040)),
+        ("rp235xa", RP(RpVariant::RP235xA)),
+        ("rp235xb", RP(RpVariant::RP235xB)),
+        ("rp2350a", RP(RpVariant::RP235xA)),
+        ("rp2350b", RP(RpVariant::RP235xB)),
+        ("rp2354a", RP(RpVariant::RP235xA)),
+        ("rp2354b", RP(RpVariant::RP235xB)),
+    ]
+}
+
+/// probe-rs targets of coprocessors and debug setups, which share the name of a supported chip
+/// but cannot run a project of their own.
+const AUXILIARY: &[&str] = &["esp32c6_lp", "rp2040_selfdebug"];
+
+/// Whether a chip name, e.g. of a probe-rs target, is of a supported chip.
+pub fn supported(name: &str) -> bool {
+    let name = name.replace('-', "_").to_lowercase();
+
+    !AUXILIARY.contains(&name.as_str())
+        && chips().iter().any(|(prefix, _)| name.starts_with(prefix))
+}
+
+/// The other security domains of a chip which `init` accepts, e.g. `nrf5340_app_ns` for the
+/// probe-rs target `nrf5340_xxaa`.
+pub fn security_domains(name: &str) -> Vec<&'static str> {
+    let Some((key, _)) = chips().iter().find(|(prefix, _)| name.starts_with(prefix)) else {
+        return Vec::new();
+    };
+
+    chips()
+        .iter()
+        .map(|(prefix, _)| *prefix)
+        .filter(|prefix| {
+            prefix
+                .strip_prefix(key)
+                .is_some_and(|suffix| suffix.starts_with('_'))
         })
+        .collect()
+}
+
+/// The error for a chip name which matches no supported chip, suggesting the closest names of
+/// probe-rs targets and supported prefixes.
+pub fn unknown(chip: &str) -> Error {
+    let mut names: Vec<_> = families()
+        .iter()
+        .flat_map(|family| &family.variants)
+        .map(|variant| variant.name.replace('-', "_").to_lowercase())
+        .filter(|name| supported(name))
+        .chain(chips().iter().map(|(prefix, _)| prefix.to_string()))
+        .map(|name| (damerau_levenshtein(chip, &name), name))
+        .filter(|(distance, _)| *distance <= 3)
+        .collect();
+    names.sort();
+    names.dedup();
+
+    Error::InvalidChip(InvalidChip::Unknown {
+        chip: chip.into(),
+        suggestions: names.into_iter().take(3).map(|(_, name)| name).collect(),
+    })
+}
+
+/// The name, probe-rs target name, (first) core and core count of chips which the bundled
+/// probe-rs does not know yet.
+pub const FALLBACKS: &[(&str, &str, CoreType, usize)] = &[
+    ("nrf54l15-app-s", "nRF54L15", CoreType::Armv8m, 2),
+    ("nrf54l10-app-s", "nRF54L10", CoreType::Armv8m, 2),
+    ("nrf54l05-app-s", "nRF54L05", CoreType::Armv8m, 2),
+    ("rp235xa", "RP235x", CoreType::Armv8m, 2),
+    ("rp235xb", "RP235x", CoreType::Armv8m, 2),
+];
+
+fn fallback(name: &str) -> Option<(&'static str, CoreType)> {
+    FALLBACKS
+        .iter()
+        .find_map(|(chip, probe_target, core, _)| (*chip == name).then_some((*probe_target, *core)))
+}
+
+/// probe-rs does not record whether a core has an FPU, so the few parts without one are listed.
+fn has_fpu(chip: &str) -> bool {
+    !["nrf52805", "nrf52810", "nrf52811", "nrf52820", "stm32wl"]
+        .iter()
+        .any(|prefix| chip.starts_with(prefix))
+}
+
+/// Whether the rest of an nRF chip name names its package, e.g. `_xxaa`, or the core and security
+/// domain generated for it, e.g. `_app_s`, so other cores like `nrf5340_net` are not mistaken for
+/// the application core.
+fn nrf_suffix_supported(key: &str, suffix: &str) -> bool {
+    suffix.is_empty()
+        || suffix.starts_with("_xx")
+        || nrf_feature(key).replace('-', "_") == format!("{key}{suffix}")
+}
+
+/// The `embassy-nrf` feature of a chip, which names the core and security domain of nRF53 and
+/// nRF91 parts, e.g. `nrf5340-app-s`.
+fn nrf_feature(key: &str) -> String {
+    match key {
+        "nrf5340" | "nrf54l15" | "nrf54l10" | "nrf54l05" => format!("{key}-app-s"),
+        "nrf9160" => "nrf9160-s".into(),
+        // FRAGILE: "_" is used to coerce probe-rs chip search
+        _ if key.contains("_xx") => key.split('_').next().unwrap().into(),
+        _ => key.replace('_', "-"),
     }
 }
@@ -1,16 +1,33 @@
 pub mod esp;
 pub mod mem_region;
+pub mod rp;
+pub mod stm;
 
 use esp::Variant;
 use mem_region::MemRegion;
+use rp::Variant as RpVariant;
 use std::fmt::Display;
 
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone)]
 pub enum Family {
     STM,
-    NRF(MemRegion),
+    /// The memory layout of chips which the memory map of probe-rs does not describe.
+    NRF(Option<MemRegion>),
     ESP(Variant),
+    RP(RpVariant),
+}
+
+impl Family {
+    /// The HAL crate of the family.
+    pub fn hal(&self) -> &'static str {
+        match self {
+            Self::STM => "embassy-stm32",
+            Self::NRF(_) => "embassy-nrf",
+            Self::ESP(_) => "esp-hal",
+            Self::RP(_) => "embassy-rp",
+        }
+    }
 }
 
 impl Display for Family {
@@ -19,6 +36,7 @@ impl Display for Family {
             Self::STM => "stm32",
             Self::NRF(_) => "nrf",
             Self::ESP(_) => "esp",
+            Self::RP(_) => "rp",
         })
     }
 }
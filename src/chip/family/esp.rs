@@ -1,8 +1,13 @@
+use crate::chip::target::Target;
 use std::fmt::Display;
 
 #[derive(Clone, Debug)]
 pub enum Variant {
+    ESP32,
+    C2,
     C3,
+    C6,
+    H2,
     S2,
     S3,
 }
@@ -10,9 +15,25 @@ pub enum Variant {
 impl Display for Variant {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
+            Self::ESP32 => "esp32",
+            Self::C2 => "esp32c2",
             Self::C3 => "esp32c3",
+            Self::C6 => "esp32c6",
+            Self::H2 => "esp32h2",
             Self::S2 => "esp32s2",
             Self::S3 => "esp32s3",
         })
     }
 }
+
+impl Variant {
+    pub fn target(&self) -> Target {
+        match self {
+            Self::ESP32 => Target::XTensaEsp32,
+            Self::C2 | Self::C3 => Target::Risc32Imc,
+            Self::C6 | Self::H2 => Target::Risc32Imac,
+            Self::S2 => Target::XTensaS2,
+            Self::S3 => Target::XTensaS3,
+        }
+    }
+}
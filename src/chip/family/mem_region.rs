@@ -8,41 +8,48 @@ pub struct MemRegion {
 }
 
 impl MemRegion {
-    pub const NRF52805: Self = Self {
-        flash_origin: 0,
-        flash_length: 192,
-        ram_origin: 0x2 << 28,
-        ram_length: 24,
+    /// Leaves the first 320K of flash and 64K of RAM to the secure firmware.
+    pub const NRF5340_APP_NS: Self = Self {
+        flash_origin: 0x50000,
+        flash_length: 704,
+        ram_origin: 0x2001_0000,
+        ram_length: 192,
     };
-    pub const NRF52810: Self = Self::NRF52805;
-    pub const NRF52811: Self = Self::NRF52805;
 
-    pub const NRF52820: Self = Self {
+    /// Leaves the start of RAM to be shared with the modem.
+    pub const NRF9160_S: Self = Self {
         flash_origin: 0,
-        flash_length: 256,
-        ram_origin: 0x2 << 28,
-        ram_length: 32,
+        flash_length: 1024,
+        ram_origin: 0x2001_8000,
+        ram_length: 160,
     };
 
-    pub const NRF52832_XXAA: Self = Self {
+    /// Leaves the first 320K of flash and 128K of RAM to the secure firmware and modem.
+    pub const NRF9160_NS: Self = Self {
+        flash_origin: 0x50000,
+        flash_length: 704,
+        ram_origin: 0x2002_0000,
+        ram_length: 128,
+    };
+
+    pub const NRF54L15: Self = Self {
         flash_origin: 0,
-        flash_length: 512,
+        flash_length: 1524,
         ram_origin: 0x2 << 28,
-        ram_length: 64,
+        ram_length: 256,
     };
-    pub const NRF52832_XXAB: Self = Self::NRF52820;
 
-    pub const NRF52833: Self = Self {
+    pub const NRF54L10: Self = Self {
         flash_origin: 0,
-        flash_length: 512,
+        flash_length: 1012,
         ram_origin: 0x2 << 28,
-        ram_length: 128,
+        ram_length: 192,
     };
 
-    pub const NRF52840: Self = Self {
+    pub const NRF54L05: Self = Self {
         flash_origin: 0,
-        flash_length: 1024,
+        flash_length: 500,
         ram_origin: 0x2 << 28,
-        ram_length: 256,
+        ram_length: 96,
     };
 }
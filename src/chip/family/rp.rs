@@ -0,0 +1,19 @@
+use std::fmt::Display;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Debug)]
+pub enum Variant {
+    RP2040,
+    RP235xA,
+    RP235xB,
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::RP2040 => "rp2040",
+            Self::RP235xA => "rp235xa",
+            Self::RP235xB => "rp235xb",
+        })
+    }
+}
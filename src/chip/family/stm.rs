@@ -0,0 +1,100 @@
+use crate::versions;
+use probe_rs::config::Target;
+
+/// The `embassy-stm32` feature of a probe-rs target, e.g. `stm32g031k8` for `STM32G031K8Tx` or
+/// `stm32h755zi-cm7` for `STM32H755ZITx`, or `None` if `embassy-stm32` has none.
+pub fn feature(target: &Target, features: &[String]) -> Option<String> {
+    let name = target.name.to_lowercase();
+
+    // the feature of dual core chips names the core to build for, which is the first one
+    let core = match target.cores.as_slice() {
+        [first, _, ..] if first.name.starts_with("cm") => Some(first.name.as_str()),
+        // e.g. the "application" core of the STM32WL5x
+        [_, _, ..] => Some("cm4"),
+        _ => None,
+    };
+
+    let chip = features
+        .iter()
+        .map(|feature| feature.split('-').next().unwrap())
+        .filter(|chip| chip.starts_with("stm32") && name.starts_with(chip))
+        .max_by_key(|chip| chip.len())?;
+
+    if features.iter().any(|feature| feature == chip) {
+        return Some(chip.into());
+    }
+
+    let dual_core = |feature: &&String| {
+        feature
+            .strip_prefix(chip)
+            .is_some_and(|suffix| suffix.starts_with("-cm"))
+    };
+
+    features
+        .iter()
+        .filter(dual_core)
+        .find(|feature| core.is_some_and(|core| feature.ends_with(core)))
+        .or_else(|| features.iter().find(dual_core))
+        .cloned()
+}
+
+/// The chip features of `embassy-stm32`, from the newest version in the local registry cache
+/// which is compatible with the pinned one, or the bundled list.
+pub fn features() -> Vec<String> {
+    crate::registry::discover(|name| versions::EMBASSY.get(name).map(String::from))
+        .into_iter()
+        .find(|krate| krate.name == "embassy-stm32")
+        .map(|krate| krate.features)
+        .unwrap_or_else(bundled_features)
+}
+
+fn bundled_features() -> Vec<String> {
+    include_str!("stm/features.txt")
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use probe_rs::config::get_target_by_name;
+
+    fn bundled(name: &str) -> Option<String> {
+        feature(&get_target_by_name(name).unwrap(), &bundled_features())
+    }
+
+    #[test]
+    fn strips_package() {
+        assert_eq!(bundled("STM32G031K8Tx").as_deref(), Some("stm32g031k8"));
+    }
+
+    #[test]
+    fn strips_package_with_suffix() {
+        assert_eq!(bundled("STM32G071K8TxN").as_deref(), Some("stm32g071k8"));
+        assert_eq!(bundled("STM32G0B1CBTxN").as_deref(), Some("stm32g0b1cb"));
+    }
+
+    #[test]
+    fn dual_core_cm7() {
+        assert_eq!(bundled("STM32H755ZITx").as_deref(), Some("stm32h755zi-cm7"));
+    }
+
+    #[test]
+    fn wl_application_core() {
+        assert_eq!(bundled("STM32WL55JCIx").as_deref(), Some("stm32wl55jc-cm4"));
+    }
+
+    #[test]
+    fn flash_size() {
+        assert_eq!(bundled("STM32WBA52CEUx").as_deref(), Some("stm32wba52ce"));
+        assert_eq!(bundled("STM32WBA52CGUx").as_deref(), Some("stm32wba52cg"));
+    }
+
+    #[test]
+    fn unknown_chip() {
+        let target = get_target_by_name("STM32G031K8Tx").unwrap();
+        assert_eq!(feature(&target, &["stm32f401re".into()]), None);
+    }
+}
@@ -0,0 +1,122 @@
+use super::family::mem_region::MemRegion;
+use crate::error::Error;
+use probe_rs::config::{MemoryRegion, Target};
+
+/// A named memory region of `memory.x`.
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub name: String,
+    pub origin: u64,
+    pub length: u64,
+}
+
+/// The memory layout of a chip, as written to `memory.x`.
+#[derive(Debug, Clone)]
+pub struct Memory {
+    pub flash: Region,
+    /// The RAM region holding the stack and statics.
+    pub ram: Region,
+    /// Additional RAM regions (e.g. `CCMRAM`, `SRAM2`, `DTCMRAM`), placed in sections of the same
+    /// name.
+    pub extra: Vec<Region>,
+}
+
+impl Memory {
+    /// Reads the memory map of the first core of a probe-rs target.
+    ///
+    /// The main RAM is the region named `ram` if given, otherwise the largest one.
+    pub fn from_probe_target(target: &Target, ram: Option<&str>) -> Result<Self, Error> {
+        let core = target.cores.first().map(|core| core.name.as_str());
+        let regions = target
+            .memory_map
+            .iter()
+            .filter(|region| core.is_none_or(|core| region.cores().iter().any(|c| c == core)));
+
+        let flash = regions
+            .clone()
+            .filter_map(|region| match region {
+                MemoryRegion::Nvm(nvm) if !nvm.is_alias => Some(nvm),
+                _ => None,
+            })
+            // boot memory first, then the largest
+            .max_by_key(|nvm| (nvm.is_boot_memory, nvm.range.end - nvm.range.start))
+            .map(|nvm| Region {
+                name: "FLASH".into(),
+                origin: nvm.range.start,
+                length: nvm.range.end - nvm.range.start,
+            })
+            .ok_or(Error::InvalidMemoryMap(target.name.clone()))?;
+
+        let rams = regions
+            .filter_map(MemoryRegion::as_ram_region)
+            .map(|ram| Region {
+                name: ram.name.as_deref().map(section_name).unwrap_or_default(),
+                origin: ram.range.start,
+                length: ram.range.end - ram.range.start,
+            })
+            .collect();
+
+        Self::new(flash, rams, ram).ok_or(match ram {
+            Some(ram) => Error::UnknownRamRegion(ram.into()),
+            None => Error::InvalidMemoryMap(target.name.clone()),
+        })
+    }
+
+    /// Uses a hand-written memory layout, for chips the bundled probe-rs does not know or
+    /// partitions of their memory (e.g. the non-secure domain).
+    pub fn from_mem_region(region: &MemRegion, ram: Option<&str>) -> Result<Self, Error> {
+        let flash = Region {
+            name: "FLASH".into(),
+            origin: region.flash_origin as u64,
+            length: region.flash_length as u64 * 1024,
+        };
+        let rams = vec![Region {
+            name: "RAM".into(),
+            origin: region.ram_origin as u64,
+            length: region.ram_length as u64 * 1024,
+        }];
+
+        Self::new(flash, rams, ram).ok_or(Error::UnknownRamRegion(ram.unwrap_or_default().into()))
+    }
+
+    fn new(flash: Region, mut rams: Vec<Region>, ram: Option<&str>) -> Option<Self> {
+        let main = match ram {
+            Some(ram) => rams
+                .iter()
+                .position(|region| region.name == section_name(ram))?,
+            // `max_by_key` returns the last maximum, which would prefer aliases listed later
+            None => {
+                rams.iter()
+                    .enumerate()
+                    .rev()
+                    .max_by_key(|(_, region)| region.length)?
+                    .0
+            }
+        };
+
+        let mut ram = rams.remove(main);
+        ram.name = "RAM".into();
+
+        // unnamed regions cannot be placed in sections, and are often aliases of the main RAM
+        rams.retain(|region| !region.name.is_empty() && !["FLASH", "RAM"].contains(&&*region.name));
+
+        Some(Self {
+            flash,
+            ram,
+            extra: rams,
+        })
+    }
+}
+
+/// Turns a probe-rs region name like `RAM_D2` or `IRAM2` into a linker identifier.
+fn section_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
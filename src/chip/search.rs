@@ -0,0 +1,105 @@
+use super::memory::Memory;
+use probe_rs::config::{get_target_by_name, search_chips, Target};
+
+/// Searches probe-rs for a chip, dropping `_` separated suffixes probe-rs doesn't know about,
+/// like the core and security domain in `nrf5340_app_ns`.
+///
+/// Returns the query which found the candidates.
+pub fn search(name: &str) -> (&str, Vec<Target>) {
+    let mut query = name;
+
+    loop {
+        let candidates: Vec<_> = search_chips(query)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|chip| get_target_by_name(chip).ok())
+            .collect();
+
+        if !candidates.is_empty() {
+            return (query, candidates);
+        }
+
+        match query.rsplit_once('_') {
+            Some((prefix, _)) => query = prefix,
+            None => return (name, candidates),
+        }
+    }
+}
+
+/// Picks the candidate a query unambiguously refers to, if any.
+pub fn pick(query: &str, candidates: &[Target]) -> Option<usize> {
+    let unique = |matches: &dyn Fn(&str) -> bool| {
+        let mut found = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, target)| matches(&target.name.to_lowercase()));
+
+        match (found.next(), found.next()) {
+            (Some((i, _)), None) => Some(i),
+            _ => None,
+        }
+    };
+
+    match candidates.len() {
+        0 => return None,
+        1 => return Some(0),
+        _ => {}
+    }
+
+    // e.g. `rp2040` over `RP2040_SELFDEBUG`
+    unique(&|name| name == query)
+        // e.g. `esp32` for `esp32-3.3v`
+        .or_else(|| unique(&|name| name.split(['-', '_']).next() == Some(query)))
+        // candidates which only differ by package, e.g. `STM32G031K8Tx` and `STM32G031K8Ux`
+        .or_else(|| {
+            candidates
+                .windows(2)
+                .all(|pair| {
+                    pair[0].memory_map == pair[1].memory_map
+                        && pair[0].cores.len() == pair[1].cores.len()
+                        && pair[0]
+                            .cores
+                            .iter()
+                            .zip(&pair[1].cores)
+                            .all(|(a, b)| a.core_type == b.core_type)
+                })
+                .then_some(0)
+        })
+}
+
+/// A line describing a candidate in the chip picker.
+pub fn describe(target: &Target) -> String {
+    let mut description = target.name.clone();
+
+    if let Ok(memory) = Memory::from_probe_target(target, None) {
+        description.push_str(&format!(
+            "  flash {}K, RAM {}K",
+            memory.flash.length / 1024,
+            memory.ram.length / 1024
+        ));
+    }
+
+    if let Some(package) = stm32_package(&target.name) {
+        description.push_str(&format!(", {package}"));
+    }
+
+    description
+}
+
+/// The package of an STM32 from the second to last letter of its name, e.g. `T` in
+/// `STM32G031K8Tx`.
+fn stm32_package(name: &str) -> Option<&'static str> {
+    if !name.starts_with("STM32") || !name.ends_with('x') {
+        return None;
+    }
+
+    match name.chars().rev().nth(1)? {
+        'T' => Some("LQFP"),
+        'U' => Some("UFQFPN"),
+        'Y' => Some("WLCSP"),
+        'P' => Some("TSSOP"),
+        'M' => Some("SO"),
+        'H' | 'I' | 'K' => Some("BGA"),
+        _ => None,
+    }
+}
@@ -1,3 +1,4 @@
+use probe_rs::CoreType;
 use std::fmt::Display;
 
 #[derive(Debug, Clone)]
@@ -7,9 +8,11 @@ pub enum Target {
     Thumbv7e,
     Thumbv7f,
     Thumbv8,
+    XTensaEsp32,
     XTensaS2,
     XTensaS3,
     Risc32Imc,
+    Risc32Imac,
 }
 
 impl Display for Target {
@@ -20,9 +23,25 @@ impl Display for Target {
             Self::Thumbv7e => "thumbv7em-none-eabi",
             Self::Thumbv7f => "thumbv7em-none-eabihf",
             Self::Thumbv8 => "thumbv8m.main-none-eabihf",
+            Self::XTensaEsp32 => "xtensa-esp32-none-elf",
             Self::XTensaS2 => "xtensa-esp32s2-none-elf",
             Self::XTensaS3 => "xtensa-esp32s3-none-elf",
             Self::Risc32Imc => "riscv32imc-unknown-none-elf",
+            Self::Risc32Imac => "riscv32imac-unknown-none-elf",
         })
     }
 }
+
+impl Target {
+    /// The target of an ARM core, `None` for other architectures.
+    pub fn from_core(core: CoreType, fpu: bool) -> Option<Self> {
+        match core {
+            CoreType::Armv6m => Some(Self::Thumbv6),
+            CoreType::Armv7m => Some(Self::Thumbv7),
+            CoreType::Armv7em if fpu => Some(Self::Thumbv7f),
+            CoreType::Armv7em => Some(Self::Thumbv7e),
+            CoreType::Armv8m => Some(Self::Thumbv8),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{
+    chip::{
+        self,
+        family::{stm, Family},
+        memory::Memory,
+        Chip, FALLBACKS,
+    },
+    cli::chips_args::ChipsArgs,
+};
+use probe_rs::config::{families, get_target_by_name};
+use serde_json::{json, Value};
+use std::fmt::Write;
+
+pub fn list(args: ChipsArgs) {
+    let search = args.search.map(|term| term.to_lowercase());
+
+    let known = families()
+        .iter()
+        .flat_map(|family| &family.variants)
+        .map(|variant| variant.name.clone())
+        .filter(|name| chip::supported(name))
+        .collect::<Vec<_>>();
+
+    let stm_features = stm::features();
+    let mut listings = Vec::new();
+
+    for name in known {
+        let Ok(target) = get_target_by_name(&name) else {
+            continue;
+        };
+        let Ok(mut chip) = Chip::new(
+            &name.replace('-', "_").to_lowercase(),
+            target.cores.first().map(|core| core.core_type),
+        ) else {
+            continue;
+        };
+
+        if let Family::STM = chip.family {
+            match stm::feature(&target, &stm_features) {
+                Some(feature) => chip.name = feature,
+                None => continue,
+            }
+        }
+
+        let domains = chip::security_domains(&name.to_lowercase());
+        listings.push(Listing {
+            name,
+            cores: target.cores.len(),
+            memory: chip.memory(Some(&target), None).ok().flatten(),
+            chip,
+        });
+
+        // e.g. the non-secure domain, which probe-rs does not list as a target of its own
+        for domain in domains {
+            let Ok(chip) = Chip::new(domain, target.cores.first().map(|core| core.core_type))
+            else {
+                continue;
+            };
+
+            listings.push(Listing {
+                name: domain.replace('_', "-"),
+                cores: target.cores.len(),
+                memory: chip.memory(Some(&target), None).ok().flatten(),
+                chip,
+            });
+        }
+    }
+
+    // chips which are only known by their name
+    for (name, _, _, cores) in FALLBACKS {
+        let Ok(chip) = Chip::new(&name.replace('-', "_"), None) else {
+            continue;
+        };
+
+        listings.push(Listing {
+            name: name.to_string(),
+            cores: *cores,
+            memory: chip.memory(None, None).ok().flatten(),
+            chip,
+        });
+    }
+
+    listings.retain(|listing| {
+        search
+            .as_ref()
+            .is_none_or(|term| listing.name.to_lowercase().contains(term))
+            && args
+                .family
+                .as_ref()
+                .is_none_or(|family| listing.chip.family.to_string() == family.to_lowercase())
+    });
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&Value::Array(listings.iter().map(to_json).collect()))
+                .unwrap()
+        );
+    } else {
+        print!("{}", render(&listings));
+    }
+}
+
+/// A chip `init` can handle.
+struct Listing {
+    /// The probe-rs target name, or the name of the chip if probe-rs does not know it.
+    name: String,
+    chip: Chip,
+    cores: usize,
+    /// `None` for families which bring their own linker scripts.
+    memory: Option<Memory>,
+}
+
+fn to_json(listing: &Listing) -> Value {
+    json!({
+        "name": listing.name,
+        "family": listing.chip.family.to_string(),
+        "target": listing.chip.target.to_string(),
+        "cores": listing.cores,
+        "memory": listing.memory.as_ref().map(|memory| {
+            [&memory.flash, &memory.ram]
+                .into_iter()
+                .chain(&memory.extra)
+                .map(|region| json!({
+                    "name": region.name,
+                    "origin": region.origin,
+                    "length": region.length,
+                }))
+                .collect::<Vec<_>>()
+        }),
+        "hal": {
+            "crate": listing.chip.family.hal(),
+            "feature": listing.chip.name,
+        },
+    })
+}
+
+fn render(listings: &[Listing]) -> String {
+    if listings.is_empty() {
+        return "No matching chips.\n".into();
+    }
+
+    let rows: Vec<[String; 6]> = listings
+        .iter()
+        .map(|listing| {
+            let memory = listing.memory.as_ref().map_or("-".into(), |memory| {
+                [&memory.flash, &memory.ram]
+                    .into_iter()
+                    .chain(&memory.extra)
+                    .map(|region| format!("{} {}K", region.name, region.length / 1024))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            });
+
+            [
+                listing.name.clone(),
+                listing.chip.family.to_string(),
+                listing.chip.target.to_string(),
+                listing.cores.to_string(),
+                format!("{}/{}", listing.chip.family.hal(), listing.chip.name),
+                memory,
+            ]
+        })
+        .collect();
+
+    let header = ["CHIP", "FAMILY", "TARGET", "CORES", "HAL FEATURE", "MEMORY"].map(String::from);
+    let mut widths = [0; 6];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, column) in widths.iter_mut().zip(row) {
+            *width = (*width).max(column.len());
+        }
+    }
+
+    let mut text = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        for (column, width) in row.iter().zip(widths) {
+            write!(text, "{column:width$}  ").unwrap();
+        }
+        text.truncate(text.trim_end().len());
+        text.push('\n');
+    }
+
+    text
+}
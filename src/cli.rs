@@ -1,6 +1,10 @@
+pub mod chips_args;
+pub mod feature_args;
 pub mod init_args;
 
+use chips_args::ChipsArgs;
 use clap::{Parser, Subcommand};
+use feature_args::{FeatureArgs, FeatureListArgs};
 use init_args::InitArgs;
 
 #[derive(Parser)]
@@ -22,12 +26,16 @@ pub enum Embassy {
         about = "Tools related to features in the Embassy ecosystem"
     )]
     Feature(Feature),
+    #[command(about = "Lists or searches the chips a project can be initialized for")]
+    Chips(ChipsArgs),
 }
 
 #[derive(Debug, Clone, Subcommand)]
 pub enum Feature {
     #[command(about = "View a list of the available features")]
-    List,
+    List(FeatureListArgs),
     #[command(about = "Add a feature to your project")]
-    Add,
+    Add(FeatureArgs),
+    #[command(about = "Remove a feature and the features depending on it from your project")]
+    Remove(FeatureArgs),
 }
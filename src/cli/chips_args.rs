@@ -0,0 +1,16 @@
+use clap::Args;
+
+#[derive(Debug, Clone, Args)]
+pub struct ChipsArgs {
+    #[arg(help = "Only show chips whose name contains this term.")]
+    pub search: Option<String>,
+
+    #[arg(
+        long,
+        help = "Only show chips of this family (e.g. stm32, nrf, esp, rp)."
+    )]
+    pub family: Option<String>,
+
+    #[arg(long, help = "Print the chips as JSON.", default_value_t = false)]
+    pub json: bool,
+}
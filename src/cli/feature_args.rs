@@ -0,0 +1,28 @@
+pub mod format;
+
+use clap::Args;
+use format::Format;
+
+#[derive(Debug, Clone, Args)]
+pub struct FeatureArgs {
+    #[arg(help = "The feature to modify, in the form `crate/feature`.")]
+    pub feature: String,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct FeatureListArgs {
+    #[arg(help = "Only show features whose name or description contains this term.")]
+    pub search: Option<String>,
+
+    #[arg(long = "crate", help = "Only show features of this crate.")]
+    pub krate: Option<String>,
+
+    #[arg(
+        long,
+        help = "Only show features applicable to this chip family (e.g. stm32, nrf, esp)."
+    )]
+    pub family: Option<String>,
+
+    #[arg(value_enum, long, help = "Selects the output format.", default_value_t = Format::Text)]
+    pub format: Format,
+}
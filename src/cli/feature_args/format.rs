@@ -0,0 +1,9 @@
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Default, PartialEq, ValueEnum)]
+#[value()]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
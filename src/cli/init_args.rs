@@ -7,8 +7,25 @@ use soft_device::Softdevice;
 
 #[derive(Debug, Clone, Args)]
 pub struct InitArgs {
-    #[arg(help = "The name of the Embassy project to create.")]
-    pub name: String,
+    #[arg(
+        help = "The name of the Embassy project to create, or `.` to initialize the current directory.",
+        required_unless_present = "in_place"
+    )]
+    pub name: Option<String>,
+
+    #[arg(
+        long,
+        help = "Initialize an existing crate or directory instead of creating a new project. Defaults to the current directory.",
+        default_value_t = false
+    )]
+    pub in_place: bool,
+
+    #[arg(
+        long,
+        help = "Overwrite existing files which were not generated when initializing in place.",
+        default_value_t = false
+    )]
+    pub force: bool,
 
     #[arg(long = "chip", help = "Specifies the target chip.")]
     pub chip_name: String,
@@ -16,9 +33,36 @@ pub struct InitArgs {
     #[arg(value_enum, long, help = "Selects the panic handler.", default_value_t = PanicHandler::Halt)]
     pub panic_handler: PanicHandler,
 
+    #[arg(
+        long,
+        help = "Selects the RAM region holding the stack by its name in the memory map, e.g. SRAM1 (STM and NRF only)."
+    )]
+    pub ram: Option<String>,
+
     #[arg(long, help = "Configure for use with a Softdevice (NRF only).")]
     pub softdevice: Option<Softdevice>,
 
+    #[arg(
+        long,
+        help = "Add the project to the enclosing cargo workspace, with the profiles and target configuration in the workspace root.",
+        default_value_t = false
+    )]
+    pub workspace: bool,
+
+    #[arg(
+        long,
+        help = "Only use dependencies in the local cache, and configure cargo to build offline.",
+        default_value_t = false
+    )]
+    pub offline: bool,
+
+    #[arg(
+        long,
+        help = "Print the files which would be created and their contents, without creating them.",
+        default_value_t = false
+    )]
+    pub dry_run: bool,
+
     #[arg(
         long,
         help = "Generate config files for vscode.",
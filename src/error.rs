@@ -1,17 +1,219 @@
+use std::fmt::Display;
+
 #[derive(Debug)]
 pub enum Error {
-    CargoAdd(String),
+    /// A cargo command exited unsuccessfully, with its exit code unless it was killed.
+    CargoFailed {
+        command: String,
+        status: Option<i32>,
+        stderr: String,
+    },
+    /// A cargo command could not be started.
+    CargoSpawn {
+        command: String,
+        message: String,
+    },
     ChangeDir,
-    CreateCargo,
+    /// A file or folder with the name of the project already exists.
+    DestinationExists(String),
     CreateFile(String),
     CreateFolder(String),
     ErroneousSoftdevice,
     ErroneousPanicHandler,
     InvalidChip(InvalidChip),
+    InvalidManifest(String),
+    InvalidMemoryMap(String),
+    MissingDependency(String),
+    /// The HAL has no feature for the probe-rs target.
+    MissingHalFeature(String),
+    /// `--workspace` was given outside of a cargo workspace.
+    NoWorkspace,
+    /// Dependencies which are not in the local cache, so the project cannot be built offline.
+    NotCached(Vec<String>),
+    ReadFile(String),
+    UnknownFeature(String),
+    UnknownRamRegion(String),
+    /// A file changed by the user would be overwritten.
+    WouldOverwrite(String),
+}
+
+impl Error {
+    /// The exit code of the process failing with this error.
+    ///
+    /// Invalid options share the exit code of the usage errors reported by clap.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::DestinationExists(_)
+            | Self::ErroneousSoftdevice
+            | Self::ErroneousPanicHandler
+            | Self::MissingDependency(_)
+            | Self::UnknownFeature(_)
+            | Self::NoWorkspace
+            | Self::UnknownRamRegion(_)
+            | Self::WouldOverwrite(_) => 2,
+            Self::InvalidChip(_) | Self::InvalidMemoryMap(_) | Self::MissingHalFeature(_) => 3,
+            Self::ChangeDir
+            | Self::CreateFile(_)
+            | Self::CreateFolder(_)
+            | Self::InvalidManifest(_)
+            | Self::ReadFile(_) => 4,
+            Self::CargoFailed { .. } | Self::CargoSpawn { .. } | Self::NotCached(_) => 5,
+        }
+    }
+
+    /// What the user can do about the error.
+    pub fn hint(&self) -> Option<String> {
+        Some(match self {
+            Self::CargoFailed { command, .. } => {
+                format!("Run `{command}` yourself to investigate.")
+            }
+            Self::CargoSpawn { .. } => "Make sure cargo is installed and on your PATH.".into(),
+            Self::ChangeDir => "Check that the directory to initialize exists.".into(),
+            Self::CreateFile(_) | Self::CreateFolder(_) => {
+                "Check that the current directory is writable and the disk is not full.".into()
+            }
+            Self::DestinationExists(_) => {
+                "Choose another project name, or initialize the existing one with `--in-place`.".into()
+            }
+            Self::ErroneousSoftdevice => {
+                "Remove `--softdevice`, or select an nRF52 chip with `--chip`.".into()
+            }
+            Self::ErroneousPanicHandler => {
+                "Remove `--panic-handler`, panics are handled by `esp-backtrace`.".into()
+            }
+            Self::InvalidChip(InvalidChip::Unknown { .. }) | Self::MissingHalFeature(_) => {
+                "Run `cargo embassy chips` to list the supported chips.".into()
+            }
+            Self::InvalidChip(InvalidChip::Ambiguous(_)) => return None,
+            Self::InvalidManifest(_) => "Fix the TOML syntax errors in it.".into(),
+            Self::InvalidMemoryMap(_) => {
+                "Please report this at https://github.com/AdinAck/cargo-embassy/issues.".into()
+            }
+            Self::MissingDependency(name) => {
+                format!("Run this command in an Embassy project, or add {name} with `cargo add {name}`.")
+            }
+            Self::NoWorkspace => {
+                "Run this command in a cargo workspace, or remove `--workspace`.".into()
+            }
+            Self::NotCached(_) => {
+                "Build a project using them while online, e.g. with `cargo fetch`, or remove `--offline`.".into()
+            }
+            Self::ReadFile(_) => "Run this command in the root of an Embassy project.".into(),
+            Self::UnknownFeature(_) => {
+                "Run `cargo embassy feature list` to see the known features, written as `crate/feature`.".into()
+            }
+            Self::WouldOverwrite(_) => "Pass `--force` to overwrite it.".into(),
+            Self::UnknownRamRegion(_) => {
+                "Run `cargo embassy chips` with the chip name to see its RAM regions, or remove `--ram`.".into()
+            }
+        })
+    }
+
+    /// The error followed by its hint, as printed when a command fails.
+    pub fn report(&self) -> String {
+        match self.hint() {
+            Some(hint) => format!("Failed with error: {self}\nhint: {hint}"),
+            None => format!("Failed with error: {self}"),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CargoFailed {
+                command,
+                status,
+                stderr,
+            } => {
+                match status {
+                    Some(code) => write!(f, "`{command}` failed with exit code {code}.")?,
+                    None => write!(f, "`{command}` was terminated.")?,
+                }
+                if !stderr.is_empty() {
+                    write!(f, "\n{stderr}")?;
+                }
+
+                Ok(())
+            }
+            Self::CargoSpawn { command, message } => {
+                write!(f, "Could not run `{command}`: {message}.")
+            }
+            Self::ChangeDir => write!(f, "Could not enter the project directory."),
+            Self::DestinationExists(name) => write!(f, "\"{name}\" already exists."),
+            Self::CreateFile(name) => write!(f, "Could not create the file \"{name}\"."),
+            Self::CreateFolder(name) => write!(f, "Could not create the folder \"{name}\"."),
+            Self::ErroneousSoftdevice => {
+                write!(f, "A Softdevice can only be used with nRF52 chips.")
+            }
+            Self::ErroneousPanicHandler => {
+                write!(f, "`--panic-handler` cannot be used with ESP32s.")
+            }
+            Self::InvalidChip(e) => write!(f, "{e}"),
+            Self::InvalidManifest(path) => write!(f, "\"{path}\" is not a valid manifest."),
+            Self::InvalidMemoryMap(target) => {
+                write!(
+                    f,
+                    "The memory map of \"{target}\" has no usable flash or RAM."
+                )
+            }
+            Self::MissingDependency(name) => {
+                write!(f, "\"{name}\" is not a dependency of this project.")
+            }
+            Self::MissingHalFeature(target) => {
+                write!(f, "The HAL has no feature for \"{target}\".")
+            }
+            Self::NoWorkspace => write!(f, "The project is not inside a cargo workspace."),
+            Self::NotCached(dependencies) => write!(
+                f,
+                "The dependencies {} are not in the local cache.",
+                quoted(dependencies).join(", ")
+            ),
+            Self::ReadFile(path) => write!(f, "Could not read \"{path}\"."),
+            Self::UnknownFeature(feature) => write!(f, "Unknown feature \"{feature}\"."),
+            Self::UnknownRamRegion(ram) => {
+                write!(f, "The chip has no RAM region named \"{ram}\".")
+            }
+            Self::WouldOverwrite(path) => write!(f, "\"{path}\" would be overwritten."),
+        }
+    }
 }
 
+impl std::error::Error for Error {}
+
 #[derive(Debug)]
 pub enum InvalidChip {
-    Unknown,
-    Ambiguous,
+    /// The closest names to the unknown chip.
+    Unknown {
+        chip: String,
+        suggestions: Vec<String>,
+    },
+    /// The probe-rs targets or variants the name could refer to.
+    Ambiguous(Vec<String>),
+}
+
+impl Display for InvalidChip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown { chip, suggestions } => {
+                write!(f, "Unknown chip \"{chip}\".")?;
+                if !suggestions.is_empty() {
+                    write!(f, " Did you mean {}?", quoted(suggestions).join(", "))?;
+                }
+
+                Ok(())
+            }
+            Self::Ambiguous(candidates) => write!(
+                f,
+                "The chip name matches several chips, specify one of {}.",
+                quoted(candidates).join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InvalidChip {}
+
+fn quoted(names: &[String]) -> Vec<String> {
+    names.iter().map(|name| format!("\"{name}\"")).collect()
 }
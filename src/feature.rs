@@ -0,0 +1,342 @@
+mod catalog;
+mod project;
+
+use crate::{
+    cli::feature_args::{format::Format, FeatureArgs, FeatureListArgs},
+    error::Error,
+    manifest::Manifest,
+    registry,
+};
+use catalog::{Entry, CATALOG};
+use project::{Project, Status};
+use serde_json::{json, Value};
+use std::{fmt::Write, process::exit};
+
+pub fn list(args: FeatureListArgs) {
+    let search = args.search.map(|term| term.to_lowercase());
+    let project = Project::open();
+    let family = args
+        .family
+        .as_deref()
+        .or(project.as_ref().and_then(|project| project.family));
+    let discovered = registry::discover(|krate| project.as_ref()?.version(krate));
+
+    let mut crates: Vec<&str> = Vec::new();
+    for krate in CATALOG
+        .iter()
+        .map(|entry| entry.krate)
+        .chain(discovered.iter().map(|krate| krate.name.as_str()))
+    {
+        if !crates.contains(&krate) {
+            crates.push(krate);
+        }
+    }
+
+    let status = |entry: Option<&Entry>, krate: &str, feature: &str| {
+        let project = project.as_ref()?;
+
+        Some(match entry {
+            Some(entry) => project.status(entry),
+            None if project.enabled(krate).iter().any(|f| f == feature) => {
+                Status::Enabled(vec![feature.into()])
+            }
+            None => Status::Disabled,
+        })
+    };
+
+    let mut listings = Vec::new();
+    for krate in crates {
+        if args.krate.as_ref().is_some_and(|k| k != krate) {
+            continue;
+        }
+
+        let documented = CATALOG.iter().filter(|entry| {
+            entry.krate == krate
+                && family.is_none_or(|family| entry.applies_to(family))
+                && search.as_ref().is_none_or(|term| {
+                    entry.name.to_lowercase().contains(term)
+                        || entry.description.to_lowercase().contains(term)
+                })
+        });
+
+        listings.extend(documented.map(|entry| Listing {
+            krate,
+            feature: entry.name,
+            version: None,
+            entry: Some(entry),
+            status: status(Some(entry), krate, entry.name),
+        }));
+
+        if family.is_some_and(|family| !catalog::crate_families(krate).contains(&family)) {
+            continue;
+        }
+
+        for discovered in discovered
+            .iter()
+            .filter(|discovered| discovered.name == krate)
+        {
+            let undocumented = discovered
+                .features
+                .iter()
+                .filter(|feature| catalog::find(krate, feature).is_none())
+                .filter(|feature| search.as_ref().is_none_or(|term| feature.contains(term)));
+
+            listings.extend(undocumented.map(|feature| Listing {
+                krate,
+                feature,
+                version: Some(&discovered.version),
+                entry: None,
+                status: status(None, krate, feature),
+            }));
+        }
+    }
+
+    match args.format {
+        Format::Text => termimad::print_text(&render(&listings)),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&Value::Array(listings.iter().map(to_json).collect()))
+                .unwrap()
+        ),
+    }
+}
+
+/// A documented or discovered feature to be listed.
+struct Listing<'a> {
+    krate: &'a str,
+    feature: &'a str,
+    /// The version of the crate the feature was discovered in.
+    version: Option<&'a str>,
+    entry: Option<&'static Entry>,
+    status: Option<Status>,
+}
+
+fn to_json(listing: &Listing) -> Value {
+    let status = listing.status.as_ref().map(Status::to_json);
+
+    match listing.entry {
+        Some(entry) => json!({
+            "crate": entry.krate,
+            "feature": entry.name,
+            "documented": true,
+            "description": entry.description,
+            "families": entry.families(),
+            "conflicts": entry.conflicts,
+            "requires": entry.requires,
+            "links": entry
+                .links
+                .iter()
+                .map(|(label, url)| json!({ "label": label, "url": url }))
+                .collect::<Vec<_>>(),
+            "status": status,
+        }),
+        None => json!({
+            "crate": listing.krate,
+            "feature": listing.feature,
+            "documented": false,
+            "version": listing.version,
+            "description": null,
+            "families": catalog::crate_families(listing.krate),
+            "conflicts": [],
+            "requires": [],
+            "links": [],
+            "status": status,
+        }),
+    }
+}
+
+fn render(listings: &[Listing]) -> String {
+    let mut text = String::new();
+
+    for (i, listing) in listings.iter().enumerate() {
+        let first = i == 0 || listings[i - 1].krate != listing.krate;
+        let last = listings
+            .get(i + 1)
+            .is_none_or(|next| next.krate != listing.krate);
+
+        if first {
+            writeln!(text, "## {}\n", listing.krate).unwrap();
+        }
+
+        match listing.entry {
+            Some(entry) => {
+                write!(text, "**`{}`**", entry.name).unwrap();
+                if let Some(status) = &listing.status {
+                    write!(text, " - *{status}*").unwrap();
+                }
+                writeln!(text, "\n\n{}\n", entry.description).unwrap();
+
+                for (label, url) in entry.links {
+                    writeln!(text, "> {label}: {url}").unwrap();
+                }
+                if !entry.links.is_empty() {
+                    writeln!(text).unwrap();
+                }
+            }
+            None => {
+                // undocumented features are listed together after the documented ones
+                if first || listings[i - 1].entry.is_some() {
+                    write!(
+                        text,
+                        "*Undocumented features of version {} found in the local registry:* ",
+                        listing.version.unwrap_or_default()
+                    )
+                    .unwrap();
+                } else {
+                    text.push_str(", ");
+                }
+
+                write!(text, "`{}`", listing.feature).unwrap();
+                if let Some(Status::Enabled(_)) = listing.status {
+                    text.push_str(" (enabled)");
+                }
+
+                if last {
+                    text.push_str("\n\n");
+                }
+            }
+        }
+    }
+
+    if text.is_empty() {
+        text.push_str("No matching features.\n");
+    }
+
+    text
+}
+
+pub fn add(args: FeatureArgs) {
+    if let Err(e) = add_inner(&args) {
+        eprintln!("{}", e.report());
+        exit(e.exit_code());
+    }
+}
+
+fn add_inner(args: &FeatureArgs) -> Result<(), Error> {
+    let (krate, feature) = split(&args.feature)?;
+
+    let mut manifest = Manifest::open("Cargo.toml")?;
+    let features = manifest
+        .dependency_features(krate)
+        .ok_or(Error::MissingDependency(krate.into()))?;
+    // including the features enabled from `[features]` lists, e.g. `debug`
+    let enabled = manifest.enabled_features(krate).unwrap_or_default();
+
+    // the real feature list of the crate takes precedence over the catalog if it is cached
+    let entry = catalog::find(krate, feature);
+    let known = match registry::discover(|k| manifest.dependency_version(k))
+        .into_iter()
+        .find(|discovered| discovered.name == krate)
+    {
+        Some(discovered) => discovered.features.iter().any(|f| f == feature),
+        None => entry.is_some_and(|entry| entry.plausible(feature)),
+    };
+
+    if !known {
+        return Err(Error::UnknownFeature(args.feature.clone()));
+    }
+
+    if enabled.iter().any(|f| f == feature) {
+        println!("{krate}/{feature} is already enabled.");
+        return Ok(());
+    }
+
+    let replaced: Vec<_> = enabled
+        .into_iter()
+        .filter(|f| entry.is_some_and(|entry| entry.conflicts_with(f)))
+        .collect();
+    let mut features: Vec<_> = features
+        .into_iter()
+        .filter(|f| !replaced.contains(f))
+        .collect();
+
+    // a feature replacing one of a `[features]` list takes its place, so it stays optional
+    let references = manifest.remove_feature_references(krate, &replaced);
+    if references.is_empty() {
+        features.push(feature.into());
+    } else {
+        for (list, reference) in &references {
+            let dep = reference.split_once('/').map_or(krate, |(dep, _)| dep);
+            manifest.add_feature(list, &[format!("{dep}/{feature}")])?;
+        }
+    }
+
+    manifest.set_dependency_features(krate, &features)?;
+    manifest.save()?;
+
+    for f in replaced {
+        println!("Removed conflicting feature {krate}/{f}.");
+    }
+    if references.is_empty() {
+        println!("Added {krate}/{feature}.");
+    } else {
+        for (list, _) in references {
+            println!("Added {krate}/{feature} to the \"{list}\" feature.");
+        }
+    }
+
+    Ok(())
+}
+
+fn split(feature: &str) -> Result<(&str, &str), Error> {
+    feature
+        .split_once('/')
+        .filter(|(krate, feature)| !krate.is_empty() && !feature.is_empty())
+        .ok_or(Error::UnknownFeature(feature.into()))
+}
+
+pub fn remove(args: FeatureArgs) {
+    if let Err(e) = remove_inner(&args) {
+        eprintln!("{}", e.report());
+        exit(e.exit_code());
+    }
+}
+
+fn remove_inner(args: &FeatureArgs) -> Result<(), Error> {
+    let (krate, feature) = split(&args.feature)?;
+
+    let mut manifest = Manifest::open("Cargo.toml")?;
+    let features = manifest
+        .dependency_features(krate)
+        .ok_or(Error::MissingDependency(krate.into()))?;
+
+    let enabled = manifest.enabled_features(krate).unwrap_or_default();
+
+    if !enabled.iter().any(|f| f == feature) {
+        println!("{krate}/{feature} is not enabled.");
+        return Ok(());
+    }
+
+    // features which depend on a removed feature are removed as well
+    let mut removed = vec![feature.to_string()];
+    loop {
+        let dependents: Vec<_> = enabled
+            .iter()
+            .filter(|f| !removed.contains(f))
+            .filter(|f| catalog::find(krate, f).is_some_and(|entry| entry.requires_any(&removed)))
+            .cloned()
+            .collect();
+
+        if dependents.is_empty() {
+            break;
+        }
+
+        removed.extend(dependents);
+    }
+
+    let (dropped, features): (Vec<_>, Vec<_>) =
+        features.into_iter().partition(|f| removed.contains(f));
+
+    manifest.set_dependency_features(krate, &features)?;
+    let references = manifest.remove_feature_references(krate, &removed);
+    manifest.save()?;
+
+    for f in dropped {
+        println!("Removed {krate}/{f}.");
+    }
+    for (list, reference) in references {
+        println!("Removed {reference} from the \"{list}\" feature.");
+    }
+
+    Ok(())
+}
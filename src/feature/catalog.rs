@@ -0,0 +1,364 @@
+/// A known feature of a crate in the Embassy ecosystem.
+///
+/// Names ending in `*` are patterns, e.g. `tick-hz-*` matches `tick-hz-32_768`.
+pub(crate) struct Entry {
+    pub krate: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// The chip families this feature applies to, or all of them if empty.
+    pub families: &'static [&'static str],
+    /// Patterns of features on the same crate which cannot be enabled alongside this one.
+    pub conflicts: &'static [&'static str],
+    /// Features on the same crate which must be enabled for this one to work.
+    pub requires: &'static [&'static str],
+    /// Labelled links to examples and documentation.
+    pub links: &'static [(&'static str, &'static str)],
+}
+
+impl Entry {
+    pub fn matches(&self, krate: &str, feature: &str) -> bool {
+        self.krate == krate && pattern_matches(self.name, feature)
+    }
+
+    pub fn applies_to(&self, family: &str) -> bool {
+        self.families().contains(&family)
+    }
+
+    pub fn families(&self) -> &'static [&'static str] {
+        if self.families.is_empty() {
+            FAMILIES
+        } else {
+            self.families
+        }
+    }
+
+    pub fn requires_any(&self, features: &[String]) -> bool {
+        self.requires
+            .iter()
+            .any(|pattern| features.iter().any(|f| pattern_matches(pattern, f)))
+    }
+
+    pub fn conflicts_with(&self, feature: &str) -> bool {
+        self.conflicts
+            .iter()
+            .any(|pattern| pattern_matches(pattern, feature))
+    }
+
+    /// Whether a feature matching this entry has a plausible suffix, for when the crate is not
+    /// cached and its real feature list is unknown.
+    pub fn plausible(&self, feature: &str) -> bool {
+        let Some(suffix) = self
+            .name
+            .strip_suffix('*')
+            .and_then(|prefix| feature.strip_prefix(prefix))
+        else {
+            return true;
+        };
+        let numeric = !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit() || c == '_');
+
+        match self.name {
+            "tick-hz-*" | "task-arena-size-*" => numeric,
+            "time-driver-*" => {
+                ["any", "rtc1"].contains(&suffix)
+                    || suffix
+                        .strip_prefix("tim")
+                        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+            }
+            "arch-*" => [
+                "cortex-m",
+                "cortex-ar",
+                "riscv32",
+                "std",
+                "wasm",
+                "avr",
+                "spin",
+            ]
+            .contains(&suffix),
+            "defmt-timestamp-uptime*" => ["", "-ms", "-us", "-tms", "-tus"].contains(&suffix),
+            _ => true,
+        }
+    }
+}
+
+pub(crate) fn pattern_matches(pattern: &str, feature: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => feature.starts_with(prefix),
+        None => pattern == feature,
+    }
+}
+
+/// The chip families a crate applies to.
+pub(crate) fn crate_families(krate: &str) -> &'static [&'static str] {
+    HALS.iter()
+        .find(|(hal, _)| *hal == krate)
+        .map_or(FAMILIES, |(_, family)| std::slice::from_ref(family))
+}
+
+pub(crate) fn find(krate: &str, feature: &str) -> Option<&'static Entry> {
+    CATALOG.iter().find(|entry| entry.matches(krate, feature))
+}
+
+/// Every chip family, as displayed by [`crate::chip::family::Family`].
+pub(crate) const FAMILIES: &[&str] = &["stm32", "nrf", "esp", "rp"];
+
+/// The HAL crates which are specific to a chip family.
+pub(crate) const HALS: &[(&str, &str)] = &[
+    ("embassy-stm32", "stm32"),
+    ("embassy-nrf", "nrf"),
+    ("esp-hal", "esp"),
+    ("embassy-rp", "rp"),
+];
+
+const ALL: &[&str] = &[];
+const STM: &[&str] = &["stm32"];
+const NRF: &[&str] = &["nrf"];
+const RP: &[&str] = &["rp"];
+
+const ARCH: &[&str] = &["arch-*"];
+const TASK_ARENA: &[&str] = &["task-arena-size-*"];
+const TICK_HZ: &[&str] = &["tick-hz-*"];
+const TIME_DRIVER: &[&str] = &["time-driver-*"];
+const DEFMT_TIMESTAMP: &[&str] = &["defmt-timestamp-uptime*"];
+
+const DEFMT: &str = "Implements `defmt::Format` for the types of this crate.";
+
+pub(crate) const CATALOG: &[Entry] = &[
+    // embassy-executor
+    Entry {
+        krate: "embassy-executor",
+        name: "arch-*",
+        description: "Selects the architecture the executor runs on, e.g. `arch-cortex-m` or `arch-riscv32`.",
+        families: ALL,
+        conflicts: ARCH,
+        requires: &[],
+        links: &[("Learn more", "https://docs.embassy.dev/embassy-executor")],
+    },
+    Entry {
+        krate: "embassy-executor",
+        name: "executor-thread",
+        description: "Enables the thread-mode executor used by `#[embassy_executor::main]`.",
+        families: ALL,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    Entry {
+        krate: "embassy-executor",
+        name: "executor-interrupt",
+        description: "Enables the ability to drive an executor with an interrupt.",
+        families: ALL,
+        conflicts: &[],
+        requires: &[],
+        links: &[
+            (
+                "Example",
+                "https://github.com/embassy-rs/embassy/blob/main/examples/nrf52840/src/bin/multiprio.rs",
+            ),
+            (
+                "Learn more",
+                "https://embassy.dev/book/dev/runtime.html#_interrupts",
+            ),
+        ],
+    },
+    Entry {
+        krate: "embassy-executor",
+        name: "task-arena-size-*",
+        description: "Configures the task arena size available to the executor. *Default:* `task-arena-size-4096`.",
+        families: ALL,
+        conflicts: TASK_ARENA,
+        requires: &[],
+        links: &[(
+            "Learn more",
+            "https://embassy.dev/book/dev/faq.html#_how_do_i_set_up_the_task_arenas_on_stable",
+        )],
+    },
+    Entry {
+        krate: "embassy-executor",
+        name: "defmt",
+        description: DEFMT,
+        families: ALL,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    // embassy-sync
+    Entry {
+        krate: "embassy-sync",
+        name: "defmt",
+        description: DEFMT,
+        families: ALL,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    // embassy-futures
+    Entry {
+        krate: "embassy-futures",
+        name: "defmt",
+        description: DEFMT,
+        families: ALL,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    // embassy-time
+    Entry {
+        krate: "embassy-time",
+        name: "tick-hz-*",
+        description: "Configures the tick rate of the active time driver. This **must** be specified as the default of `1MHz` is not operational. A typical value is `tick-hz-32_768` but this should be set appropriately for the application.",
+        families: ALL,
+        conflicts: TICK_HZ,
+        requires: &[],
+        links: &[(
+            "Learn more",
+            "https://github.com/embassy-rs/embassy/tree/main/embassy-time#tick-rate",
+        )],
+    },
+    Entry {
+        krate: "embassy-time",
+        name: "defmt",
+        description: DEFMT,
+        families: ALL,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    Entry {
+        krate: "embassy-time",
+        name: "defmt-timestamp-uptime*",
+        description: "Enables the reporting of the uptime (seconds) in RTT messages. Suffixes such as `-ms` and `-us` change the unit.",
+        families: ALL,
+        conflicts: DEFMT_TIMESTAMP,
+        requires: &["defmt"],
+        links: &[],
+    },
+    // embassy-stm32
+    Entry {
+        krate: "embassy-stm32",
+        name: "exti",
+        description: "Enables the use of the EXTI peripheral.",
+        families: STM,
+        conflicts: &[],
+        requires: &[],
+        links: &[(
+            "Example",
+            "https://github.com/embassy-rs/embassy/blob/main/examples/stm32g4/src/bin/button_exti.rs",
+        )],
+    },
+    Entry {
+        krate: "embassy-stm32",
+        name: "time-driver-*",
+        description: "Configures the time driver to be provided to `embassy-time`. An exact timer to be used can be specified with `time-driver-timX` where `X` is the timer number. `embassy-stm32` can pick a time driver itself with `time-driver-any`.",
+        families: STM,
+        conflicts: TIME_DRIVER,
+        requires: &[],
+        links: &[(
+            "Learn more",
+            "https://github.com/embassy-rs/embassy/tree/18da9a2b66f21a1d1b5cd07c8567b700be8c7b09/embassy-stm32#embassy-time-time-driver",
+        )],
+    },
+    Entry {
+        krate: "embassy-stm32",
+        name: "time",
+        description: "Enables additional time related functionality. In general, enables timeouts for IO transactions.",
+        families: STM,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    Entry {
+        krate: "embassy-stm32",
+        name: "memory-x",
+        description: "Provides the `memory.x` linker script for the selected chip. The `memory.x` generated by `cargo embassy init` must be removed when enabling it.",
+        families: STM,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    Entry {
+        krate: "embassy-stm32",
+        name: "unstable-pac",
+        description: "Re-exports the peripheral access crate as `embassy_stm32::pac`. Its API is not covered by semver.",
+        families: STM,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    Entry {
+        krate: "embassy-stm32",
+        name: "defmt",
+        description: DEFMT,
+        families: STM,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    // embassy-nrf
+    Entry {
+        krate: "embassy-nrf",
+        name: "time-driver-*",
+        description: "Configures the time driver to be provided to `embassy-time`. Only one timer (`RTC1`) is available as a time driver.",
+        families: NRF,
+        conflicts: TIME_DRIVER,
+        requires: &[],
+        links: &[(
+            "Learn more",
+            "https://github.com/embassy-rs/embassy/tree/18da9a2b66f21a1d1b5cd07c8567b700be8c7b09/embassy-nrf#time-driver",
+        )],
+    },
+    Entry {
+        krate: "embassy-nrf",
+        name: "time",
+        description: "Enables additional time related functionality. In general, enables timeouts for IO transactions.",
+        families: NRF,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    Entry {
+        krate: "embassy-nrf",
+        name: "gpiote",
+        description: "Enables the GPIOTE peripheral, which is required for async GPIO input.",
+        families: NRF,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    Entry {
+        krate: "embassy-nrf",
+        name: "defmt",
+        description: DEFMT,
+        families: NRF,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    // embassy-rp
+    Entry {
+        krate: "embassy-rp",
+        name: "time-driver",
+        description: "Provides the TIMER peripheral as the time driver for `embassy-time`, ticking at 1MHz.",
+        families: RP,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    Entry {
+        krate: "embassy-rp",
+        name: "critical-section-impl",
+        description: "Provides a critical section implementation which is safe to use from both cores.",
+        families: RP,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+    Entry {
+        krate: "embassy-rp",
+        name: "defmt",
+        description: DEFMT,
+        families: RP,
+        conflicts: &[],
+        requires: &[],
+        links: &[],
+    },
+];
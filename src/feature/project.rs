@@ -0,0 +1,102 @@
+use super::catalog::{pattern_matches, Entry, CATALOG, FAMILIES, HALS};
+use crate::manifest::Manifest;
+use serde_json::{json, Value};
+use std::fmt::Display;
+
+/// The Embassy project in the current directory.
+pub(crate) struct Project {
+    manifest: Manifest,
+    pub family: Option<&'static str>,
+}
+
+pub(crate) enum Status {
+    Enabled(Vec<String>),
+    Disabled,
+    Conflicting(String),
+}
+
+impl Project {
+    pub fn open() -> Option<Self> {
+        let manifest = Manifest::open("Cargo.toml").ok()?;
+
+        // not an Embassy project
+        if !CATALOG
+            .iter()
+            .any(|entry| manifest.dependency_features(entry.krate).is_some())
+        {
+            return None;
+        }
+
+        // recorded by `init`, otherwise derived from the HAL
+        let family = manifest
+            .metadata()
+            .and_then(|metadata| FAMILIES.iter().find(|f| **f == metadata.family).copied())
+            .or_else(|| {
+                HALS.iter()
+                    .find(|(krate, _)| manifest.dependency_features(krate).is_some())
+                    .map(|(_, family)| *family)
+            });
+
+        Some(Self { manifest, family })
+    }
+
+    pub fn version(&self, krate: &str) -> Option<String> {
+        self.manifest.dependency_version(krate)
+    }
+
+    pub fn enabled(&self, krate: &str) -> Vec<String> {
+        self.manifest.enabled_features(krate).unwrap_or_default()
+    }
+
+    pub fn status(&self, entry: &Entry) -> Status {
+        let enabled = self.enabled(entry.krate);
+        let (matched, others): (Vec<_>, Vec<_>) = enabled
+            .into_iter()
+            .partition(|f| pattern_matches(entry.name, f));
+
+        if matched.is_empty() {
+            return match others.iter().find(|f| entry.conflicts_with(f)) {
+                Some(f) => Status::Conflicting(format!("conflicts with `{f}`")),
+                None => Status::Disabled,
+            };
+        }
+
+        if matched.len() > 1 && entry.conflicts_with(entry.name) {
+            return Status::Conflicting(format!("only one of {} may be enabled", quoted(&matched)));
+        }
+
+        if !entry.requires.is_empty() && !entry.requires_any(&others) {
+            return Status::Conflicting(format!("requires {}", quoted(entry.requires)));
+        }
+
+        Status::Enabled(matched)
+    }
+}
+
+impl Status {
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Enabled(features) => json!({ "state": "enabled", "features": features }),
+            Self::Disabled => json!({ "state": "disabled" }),
+            Self::Conflicting(reason) => json!({ "state": "conflicting", "reason": reason }),
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Enabled(features) => write!(f, "enabled ({})", quoted(features)),
+            Self::Disabled => f.write_str("disabled"),
+            Self::Conflicting(reason) => write!(f, "conflicting, {reason}"),
+        }
+    }
+}
+
+fn quoted(features: &[impl AsRef<str>]) -> String {
+    features
+        .iter()
+        .map(|f| format!("`{}`", f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,40 +1,77 @@
+mod dependencies;
+mod transaction;
+mod workspace;
+
 use crate::{
     chip::{
-        family::{esp::Variant, mem_region::MemRegion, Family},
-        Chip,
+        self,
+        family::{esp::Variant, rp::Variant as RpVariant, stm, Family},
+        memory::Memory,
+        search, Chip,
     },
     cli::init_args::{panic_handler::PanicHandler, soft_device::Softdevice, InitArgs},
     error::{Error, InvalidChip},
+    manifest::{Dependency, Manifest, Metadata},
 };
+use dialoguer::FuzzySelect;
 use indicatif::ProgressBar;
 use inflector::cases::snakecase::to_snake_case;
-use probe_rs::config::{get_target_by_name, search_chips};
+use probe_rs::config::Target;
 use std::{
-    env::set_current_dir,
+    cell::RefCell,
+    env::{current_dir, set_current_dir},
     fs,
-    io::{Read, Write},
-    process::Command,
+    io::{stdin, IsTerminal, Write},
+    path::Path,
+    process::{exit, Command},
     time::Duration,
 };
+use transaction::Transaction;
+use workspace::Workspace;
 
 use serde_json::Value;
 
 pub struct Init {
     pb: ProgressBar,
+    transaction: RefCell<Transaction>,
+    /// Print what would be done instead of doing it.
+    dry_run: bool,
 }
 
 impl Init {
-    pub fn new() -> Self {
-        let pb = ProgressBar::new_spinner();
+    pub fn new(dry_run: bool) -> Self {
+        let pb = if dry_run {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new_spinner()
+        };
         pb.enable_steady_tick(Duration::from_millis(100));
 
-        Self { pb }
+        Self {
+            pb,
+            transaction: RefCell::default(),
+            dry_run,
+        }
     }
 
     pub fn run(&self, args: InitArgs) {
+        let dir = current_dir();
+
         if let Err(e) = self.run_inner(args) {
-            self.pb
-                .abandon_with_message(format!("Failed with error: {e:#?}."))
+            // leave the project, so it can be removed
+            if let Ok(dir) = dir {
+                let _ = set_current_dir(dir);
+            }
+            self.transaction.take().rollback();
+
+            // the progress bar is hidden when not drawing to a terminal, e.g. in scripts
+            if self.pb.is_hidden() {
+                eprintln!("{}", e.report());
+            } else {
+                self.pb.abandon_with_message(e.report());
+            }
+
+            exit(e.exit_code());
         } else {
             self.pb
                 .finish_with_message(format!("Finished in {}s", self.pb.elapsed().as_secs()))
@@ -45,38 +82,127 @@ impl Init {
         // for convenience
         args.chip_name = args.chip_name.replace('-', "_").to_lowercase();
 
-        let (chip, probe_target_name) = self.get_target_info(&args.chip_name)?;
+        let (mut chip, probe_target_name, probe_target) = self.get_target_info(&args.chip_name)?;
+
+        if let (Family::STM, Some(probe_target)) = (&chip.family, &probe_target) {
+            chip.name = stm::feature(probe_target, &stm::features())
+                .ok_or(Error::MissingHalFeature(probe_target.name.clone()))?;
+        }
+
+        let dir = args.name.clone().unwrap_or(".".into());
+        let in_place = args.in_place || dir == ".";
+
+        if in_place {
+            set_current_dir(&dir).map_err(|_| Error::ChangeDir)?;
+
+            if let Some(file) = custom_file(args.vscode).filter(|_| !args.force) {
+                return Err(Error::WouldOverwrite(file.into()));
+            }
+        } else if Path::new(&dir).exists() {
+            return Err(Error::DestinationExists(dir));
+        }
+
+        let workspace = if args.workspace {
+            let project = current_dir().map_err(|_| Error::ChangeDir)?;
+            let project = if in_place {
+                project
+            } else {
+                project.join(&dir)
+            };
 
-        // validate softdevice <--> nrf
-        if args.softdevice.is_some() && !matches!(chip.family, Family::NRF(_)) {
+            Some(Workspace::find(&project).ok_or(Error::NoWorkspace)?)
+        } else {
+            None
+        };
+
+        // validate softdevice <--> nrf52
+        if args.softdevice.is_some()
+            && !(matches!(chip.family, Family::NRF(_)) && chip.name.starts_with("nrf52"))
+        {
             return Err(Error::ErroneousSoftdevice);
         }
 
-        self.create_project(&args.name)?;
+        if let Family::ESP(_) = &chip.family {
+            self.notice("[NOTICE] ESP32s have their own panic handler system.");
+            if args.panic_handler.ne(&PanicHandler::default()) {
+                Err(Error::ErroneousPanicHandler)?
+            }
+        }
+
+        let memory = chip.memory(probe_target.as_ref(), args.ram.as_deref())?;
+
+        let dependencies =
+            dependencies::dependencies(&chip, &args.panic_handler, args.softdevice.as_ref());
+        if args.offline {
+            let uncached = dependencies::uncached(&dependencies);
+            if !uncached.is_empty() {
+                return Err(Error::NotCached(uncached));
+            }
+        }
+
+        // cargo registers new projects in the workspace itself
+        if let Some(workspace) = &workspace {
+            self.transaction
+                .borrow_mut()
+                .track(workspace.path("Cargo.toml"));
+        }
+
+        let name = if in_place {
+            self.init_project(&dir)?;
+            package_name()
+        } else {
+            self.create_project(&dir)?;
+            dir
+        };
 
-        self.init_config(&chip, &probe_target_name)?;
+        self.init_config(
+            &chip,
+            &probe_target_name,
+            &name,
+            args.offline,
+            workspace.as_ref(),
+        )?;
         if args.vscode {
-            self.init_debug_config(&chip, &probe_target_name, &args.name)?;
+            self.init_debug_config(&chip, &probe_target_name, &name)?;
         }
-        self.init_toolchain(&chip)?;
+        self.init_toolchain(&chip, &name, workspace.as_ref())?;
         if !matches!(&chip.family, Family::ESP(_)) {
             self.init_embed(&probe_target_name)?;
         }
         self.init_build(&chip.family)?;
+        let metadata = Metadata {
+            chip: chip.name.clone(),
+            probe_target: probe_target_name.clone(),
+            family: chip.family.to_string(),
+            target: chip.target.to_string(),
+            panic_handler: (!matches!(chip.family, Family::ESP(_)))
+                .then(|| args.panic_handler.str().into()),
+            softdevice: args.softdevice.as_ref().map(|sd| sd.str().into()),
+            generator_version: env!("CARGO_PKG_VERSION").into(),
+        };
         self.init_manifest(
-            &args.name,
+            &name,
             &chip,
-            &args.panic_handler,
-            args.softdevice.as_ref(),
+            &dependencies,
+            &metadata,
+            in_place,
+            workspace.as_ref(),
         )?;
+        if args.offline {
+            self.init_lockfile(workspace.as_ref())?;
+        }
         if !matches!(&chip.family, Family::ESP(_)) {
             self.init_fmt()?;
         }
-        self.init_main(&chip.family, &args.panic_handler, args.softdevice.as_ref())?;
+        self.init_main(&chip, &args.panic_handler, args.softdevice.as_ref())?;
 
-        if let Family::NRF(mem_reg) = chip.family {
-            self.init_memory_x(mem_reg)?;
-            self.pb.println("[ACTION NEEDED] You must now flash the Softdevice and configure memory.x. Instructions can be found here: https://github.com/embassy-rs/nrf-softdevice#running-examples.");
+        match (memory, &chip.family) {
+            (Some(memory), _) => self.init_memory_x(&memory)?,
+            (None, Family::RP(variant)) => self.init_rp_memory_x(variant)?,
+            (None, _) => {}
+        }
+        if args.softdevice.is_some() {
+            self.notice("[ACTION NEEDED] You must now flash the Softdevice and configure memory.x. Instructions can be found here: https://github.com/embassy-rs/nrf-softdevice#running-examples.");
         }
 
         Ok(())
@@ -84,32 +210,107 @@ impl Init {
 
     fn create_project(&self, name: &str) -> Result<(), Error> {
         self.pb.set_message("Create cargo project");
-        Command::new("cargo")
-            .args(["new", &name])
-            .output()
-            .map_err(|_| Error::CreateCargo)?;
+        if self.dry_run {
+            println!("Would run `cargo new {name}` and create in {name}:\n");
+            return Ok(());
+        }
+
+        self.transaction.borrow_mut().track(name);
+        cargo(&["new", name])?;
 
         set_current_dir(name).map_err(|_| Error::ChangeDir)
     }
 
-    fn get_target_info(&self, name: &str) -> Result<(Chip, String), Error> {
+    /// Turns the current directory into a cargo project, unless it already is one.
+    fn init_project(&self, dir: &str) -> Result<(), Error> {
+        if self.dry_run {
+            println!("Would initialize {dir} in place and create:\n");
+        }
+        if Path::new("Cargo.toml").exists() {
+            return Ok(());
+        }
+
+        self.pb.set_message("Create cargo project");
+        if self.dry_run {
+            println!("Would run `cargo init` first.\n");
+            return Ok(());
+        }
+
+        for path in ["Cargo.toml", "src", ".gitignore", ".git"] {
+            self.transaction.borrow_mut().track(path);
+        }
+        cargo(&["init"])
+    }
+
+    fn get_target_info(&self, name: &str) -> Result<(Chip, String, Option<Target>), Error> {
         self.pb.set_message("Searching chips");
-        if let Ok(chips) = search_chips(name) {
-            let probe_target = get_target_by_name(
-                chips
-                    .first()
-                    .ok_or(Error::InvalidChip(InvalidChip::Unknown))?,
-            )
-            .unwrap();
+        let (query, mut candidates) = search::search(name);
+        candidates.retain(|target| chip::supported(&target.name));
+
+        // an interactively picked chip replaces the name given
+        let mut picked = None;
+        let probe_target = match search::pick(query, &candidates) {
+            Some(i) => Some(candidates.swap_remove(i)),
+            None if candidates.is_empty() => None,
+            None if stdin().is_terminal() => {
+                let target = self.pick_chip(candidates)?;
+                picked = Some(target.name.replace('-', "_").to_lowercase());
+                Some(target)
+            }
+            None => {
+                return Err(Error::InvalidChip(InvalidChip::Ambiguous(
+                    candidates.into_iter().map(|target| target.name).collect(),
+                )))
+            }
+        };
 
-            Ok((name.parse()?, probe_target.name))
-        } else {
-            Err(Error::InvalidChip(InvalidChip::Unknown))
+        let chip = Chip::new(
+            picked.as_deref().unwrap_or(name),
+            probe_target
+                .as_ref()
+                .and_then(|target| target.cores.first())
+                .map(|core| core.core_type),
+        )?;
+
+        match probe_target {
+            Some(probe_target) => Ok((chip, probe_target.name.clone(), Some(probe_target))),
+            None => {
+                let probe_target = chip
+                    .fallback_probe_target()
+                    .ok_or_else(|| chip::unknown(name))?;
+
+                Ok((chip, probe_target.into(), None))
+            }
         }
     }
 
+    /// Lets the user pick one of several chips matching their search.
+    fn pick_chip(&self, mut candidates: Vec<Target>) -> Result<Target, Error> {
+        let items: Vec<_> = candidates.iter().map(search::describe).collect();
+
+        let i = self
+            .pb
+            .suspend(|| {
+                FuzzySelect::new()
+                    .with_prompt("Several chips match, select one")
+                    .items(&items)
+                    .default(0)
+                    .interact_opt()
+            })
+            .ok()
+            .flatten()
+            .ok_or(Error::InvalidChip(InvalidChip::Ambiguous(
+                candidates
+                    .iter()
+                    .map(|target| target.name.clone())
+                    .collect(),
+            )))?;
+
+        Ok(candidates.swap_remove(i))
+    }
+
     fn init_debug_config(&self, chip: &Chip, name: &str, project_name: &str) -> Result<(), Error> {
-        fs::create_dir_all(".vscode").map_err(|_| Error::CreateFolder(".vscode".into()))?;
+        self.create_folder(".vscode")?;
 
         let contents = include_str!("templates/launch.json.template").to_string();
         let mut contents =
@@ -128,41 +329,148 @@ impl Init {
         )
     }
 
-    fn init_config(&self, chip: &Chip, name: &str) -> Result<(), Error> {
-        fs::create_dir_all(".cargo").map_err(|_| Error::CreateFolder(".cargo".into()))?;
+    fn init_config(
+        &self,
+        chip: &Chip,
+        probe_target: &str,
+        package: &str,
+        offline: bool,
+        workspace: Option<&Workspace>,
+    ) -> Result<(), Error> {
+        let runner = match &chip.family {
+            Family::ESP(_) => "\"espflash flash --monitor\"".into(),
+            _ => format!("'probe-rs run --chip {probe_target}'"),
+        };
 
-        self.create_file(
-            ".cargo/config.toml",
-            &match &chip.family {
-                Family::ESP(variant) => format!(
-                    include_str!("templates/config.toml.esp.template"),
-                    target = chip.target,
-                    rustflags = match variant {
-                        Variant::C3 => "rustflags = [\n\"-C\", \"force-frame-pointers\",\n]",
-                        Variant::S2 | Variant::S3 =>
-                            "rustflags = [\n\"-C\", \"link-arg=-nostartfiles\",\n]",
-                    }
-                ),
-                _ => format!(
-                    include_str!("templates/config.toml.template"),
-                    target = chip.target,
-                    chip = name
-                ),
-            },
-        )
+        let mut config = match &chip.family {
+            Family::ESP(variant) => format!(
+                include_str!("templates/config.toml.esp.template"),
+                target = chip.target,
+                runner = runner,
+                rustflags = match variant {
+                    Variant::C2 | Variant::C3 | Variant::C6 | Variant::H2 =>
+                        "rustflags = [\n\"-C\", \"force-frame-pointers\",\n]",
+                    Variant::ESP32 | Variant::S2 | Variant::S3 =>
+                        "rustflags = [\n\"-C\", \"link-arg=-nostartfiles\",\n]",
+                }
+            ),
+            _ => format!(
+                include_str!("templates/config.toml.template"),
+                target = chip.target,
+                runner = runner
+            ),
+        };
+
+        if offline {
+            config.push_str(include_str!("templates/config.toml.offline.template"));
+        }
+
+        self.create_folder(".cargo")?;
+        self.create_file(".cargo/config.toml", &config)?;
+
+        let Some(workspace) = workspace else {
+            return Ok(());
+        };
+
+        // the build target would apply to every member, so the aliases select it instead
+        let path = workspace.path(".cargo/config.toml");
+        // without the header, which belongs to the first table and would be lifted with it
+        let (_, settings) = config.split_once('\n').unwrap_or_default();
+        let mut member = Manifest::parse(".cargo/config.toml", settings)?;
+        let mut lifted = Manifest::parse(
+            &path,
+            &format!(
+                include_str!("templates/config.toml.workspace.template"),
+                package = package,
+                target = chip.target,
+                // the Xtensa targets have no prebuilt `core`
+                build_std = match chip.family {
+                    Family::ESP(_) => " -Zbuild-std=core",
+                    _ => "",
+                }
+            ),
+        )?;
+        for key in ["target", "env"] {
+            if let Some(item) = member.remove(key) {
+                lifted.insert_missing(key, &item);
+            }
+        }
+
+        self.create_folder(&workspace.path(".cargo"))?;
+        self.create_root_file(&path, &lifted.contents())?;
+        self.notice(&format!(
+            "[NOTICE] Added the runner, rustflags and environment for {target} and the `cargo build-{package}` and `cargo run-{package}` aliases to the workspace .cargo/config.toml. The build target stays in {member}/.cargo/config.toml, as it would apply to every member.",
+            target = chip.target,
+            member = workspace.member,
+        ));
+
+        Ok(())
     }
 
-    fn init_toolchain(&self, chip: &Chip) -> Result<(), Error> {
-        self.create_file(
-            "rust-toolchain.toml",
-            &match chip.family {
-                Family::ESP(_) => include_str!("templates/rust-toolchain.toml.esp.template").into(),
-                _ => format!(
-                    include_str!("templates/rust-toolchain.toml.template"),
-                    target = chip.target
-                ),
-            },
-        )
+    fn init_toolchain(
+        &self,
+        chip: &Chip,
+        package: &str,
+        workspace: Option<&Workspace>,
+    ) -> Result<(), Error> {
+        let toolchain = match chip.family {
+            Family::ESP(_) => include_str!("templates/rust-toolchain.toml.esp.template").into(),
+            _ => format!(
+                include_str!("templates/rust-toolchain.toml.template"),
+                target = chip.target
+            ),
+        };
+
+        let Some(workspace) = workspace else {
+            return self.create_file("rust-toolchain.toml", &toolchain);
+        };
+
+        let path = workspace.path("rust-toolchain.toml");
+        let generated = Manifest::parse(&path, &toolchain)?;
+        let pinned = generated.toolchain_channel().unwrap_or_default();
+
+        // a new workspace toolchain would apply to the host members as well
+        if !Path::new(&path).exists() {
+            self.notice(&format!(
+                "[NOTICE] The workspace has no rust-toolchain.toml, so the {pinned} toolchain is only selected in {member}. From the workspace root, use `cargo +{pinned} run-{package}`.",
+                member = workspace.member,
+            ));
+            return self.create_file("rust-toolchain.toml", &toolchain);
+        }
+
+        // the members share the toolchain, so it has to install the firmware target as well
+        let mut existing = Manifest::open(&path)?;
+        for target in generated.toolchain_targets() {
+            existing.add_toolchain_target(&target)?;
+        }
+        existing.merge_missing(&generated);
+
+        if let Some(channel) = existing
+            .toolchain_channel()
+            .filter(|channel| *channel != pinned)
+        {
+            self.notice(&format!(
+                "[WARNING] The workspace rust-toolchain.toml uses the {channel} channel, but the project is generated for {pinned}."
+            ));
+        }
+
+        self.create_file(&path, &existing.contents())
+    }
+
+    /// Resolves the whole dependency tree from the cache, as only the direct dependencies are
+    /// checked up front.
+    fn init_lockfile(&self, workspace: Option<&Workspace>) -> Result<(), Error> {
+        self.pb.set_message("Resolve dependencies offline");
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let lockfile = workspace.map_or("Cargo.lock".into(), |workspace| {
+            workspace.path("Cargo.lock")
+        });
+        self.transaction.borrow_mut().track(lockfile);
+
+        cargo(&["generate-lockfile", "--offline"])
     }
 
     fn init_embed(&self, chip: &str) -> Result<(), Error> {
@@ -174,164 +482,94 @@ impl Init {
 
     fn init_build(&self, family: &Family) -> Result<(), Error> {
         let template = match family {
-            Family::STM => include_str!("templates/build.rs.stm.template"),
-            Family::NRF(_) => include_str!("templates/build.rs.nrf.template"),
-            Family::ESP(_) => include_str!("templates/build.rs.esp.template"),
+            Family::STM => include_str!("templates/build.rs.stm.template").into(),
+            Family::NRF(_) => include_str!("templates/build.rs.nrf.template").into(),
+            Family::ESP(_) => include_str!("templates/build.rs.esp.template").into(),
+            Family::RP(variant) => format!(
+                include_str!("templates/build.rs.rp.template"),
+                link_rp = match variant {
+                    RpVariant::RP2040 =>
+                        "\n    println!(\"cargo:rustc-link-arg-bins=-Tlink-rp.x\");",
+                    RpVariant::RP235xA | RpVariant::RP235xB => "",
+                }
+            ),
         };
 
-        self.create_file("build.rs", template)
+        self.create_file("build.rs", &template)
     }
 
     fn init_manifest(
         &self,
         name: &str,
         chip: &Chip,
-        panic_handler: &PanicHandler,
-        softdevice: Option<&Softdevice>,
+        dependencies: &[Dependency],
+        metadata: &Metadata,
+        in_place: bool,
+        workspace: Option<&Workspace>,
     ) -> Result<(), Error> {
-        self.create_file(
+        self.pb.set_message("Generate manifest");
+        let mut generated = Manifest::parse(
             "Cargo.toml",
             &format!(include_str!("templates/Cargo.toml.template"), name = name),
         )?;
 
-        // NOTE: should be threaded proably
-        self.cargo_add(
-            "embassy-executor",
-            match &chip.family {
-                Family::ESP(_) => Some(&["executor-thread"]),
-                _ => Some(&["arch-cortex-m", "executor-thread"]),
-            },
-            None,
-            false,
-        )?;
-        self.cargo_add("embassy-sync", None, None, false)?;
-        self.cargo_add("embassy-futures", None, None, false)?;
-        self.cargo_add(
-            "embassy-time",
-            match &chip.family {
-                Family::ESP(_) => None,
-                _ => Some(&["tick-hz-32_768"]),
-            },
-            None,
-            false,
-        )?;
+        for dependency in dependencies {
+            generated.add_dependency(dependency)?;
+        }
 
-        match &chip.family {
-            Family::STM => {
-                self.cargo_add(
-                    "embassy-stm32",
-                    Some(&[
-                        "memory-x",
-                        chip.name.as_str(),
-                        "time-driver-any",
-                        "exti",
-                        "unstable-pac",
-                    ]),
-                    None,
-                    false,
-                )?;
+        if !matches!(&chip.family, Family::ESP(_)) {
+            for dep in ["defmt", "defmt-rtt", "panic-probe"] {
+                generated.add_feature(dep, &[format!("dep:{dep}")])?;
             }
-            Family::NRF(_) => {
-                self.cargo_add(
-                    "embassy-nrf",
-                    Some(&[chip.name.as_str(), "gpiote", "time-driver-rtc1"]),
-                    None,
-                    false,
-                )?;
+
+            let mut debug: Vec<String> = [
+                "defmt",
+                "defmt-rtt",
+                "panic-probe",
+                "embassy-executor/defmt",
+                "embassy-sync/defmt",
+                "embassy-futures/defmt",
+                "embassy-time/defmt",
+                "embassy-time/defmt-timestamp-uptime",
+            ]
+            .map(String::from)
+            .into();
+            debug.push(format!("{}/defmt", chip.family.hal()));
+            if metadata.softdevice.is_some() {
+                debug.push("nrf-softdevice/defmt".into());
             }
-            Family::ESP(variant) => {
-                let name = variant.to_string();
-
-                self.cargo_add("embassy-time-driver", None, None, false)?;
-                self.cargo_add(
-                    "esp-backtrace",
-                    Some(&[&name, "exception-handler", "panic-handler", "println"]),
-                    None,
-                    false,
-                )?;
-                self.cargo_add("esp-hal", Some(&[&name]), None, false)?;
-                self.cargo_add("esp-hal-embassy", Some(&[&name]), None, false)?;
-                self.cargo_add("esp-println", Some(&[&name, "log"]), None, false)?;
-                self.cargo_add("log", None, None, false)?;
-                self.cargo_add("static_cell", None, None, false)?;
+
+            generated.add_feature("default", &["debug".into()])?;
+            generated.add_feature("debug", &debug)?;
+        }
+
+        // profiles of workspace members are ignored
+        if let Some(workspace) = workspace {
+            let path = workspace.path("Cargo.toml");
+            let mut root = Manifest::open(&path)?;
+
+            root.add_workspace_member(&workspace.member)?;
+            if let Some(profile) = generated.remove("profile") {
+                root.insert_missing("profile", &profile);
             }
-        };
 
-        if let Some(softdevice) = softdevice {
-            self.cargo_add(
-                "nrf-softdevice",
-                Some(&[
-                    chip.name.as_str(),
-                    softdevice.str(),
-                    "ble-peripheral",
-                    "ble-gatt-server",
-                    "critical-section-impl",
-                ]),
-                Some("https://github.com/embassy-rs/nrf-softdevice"),
-                false,
-            )?;
-            self.cargo_add(
-                &format!("nrf-softdevice-{}", softdevice.str()),
-                None,
-                Some("https://github.com/embassy-rs/nrf-softdevice"),
-                false,
-            )?;
+            self.create_file(&path, &root.contents())?;
         }
 
-        if let Family::ESP(_) = &chip.family {
-            println!("[NOTICE] ESP32s have their own panic handler system.");
-            if panic_handler.ne(&PanicHandler::default()) {
-                Err(Error::ErroneousPanicHandler)?
+        // initializing in place keeps the existing manifest
+        let mut manifest = if in_place && Path::new("Cargo.toml").exists() {
+            let mut manifest = Manifest::open("Cargo.toml")?;
+            for (name, existing, pinned) in manifest.merge(&generated)? {
+                self.notice(&format!("[NOTICE] Replaced the existing dependency {name} = {existing} with {name} = {pinned}."));
             }
+            manifest
         } else {
-            self.cargo_add(
-                "cortex-m",
-                Some(if softdevice.is_some() {
-                    &["inline-asm"]
-                } else {
-                    &["inline-asm", "critical-section-single-core"]
-                }),
-                None,
-                false,
-            )?;
-            self.cargo_add("cortex-m-rt", None, None, false)?;
-            self.cargo_add("defmt", None, None, true)?;
-            self.cargo_add("defmt-rtt", None, None, true)?;
-            self.cargo_add("panic-probe", Some(&["print-defmt"]), None, true)?;
-            self.cargo_add(panic_handler.str(), None, None, false)?;
-
-            let mut file = fs::OpenOptions::new()
-                .read(true)
-                .append(true)
-                .open("Cargo.toml")
-                .map_err(|_| Error::CreateFile("Cargo.toml".into()))?;
-
-            // really gross patch for cargo version discontinuity
-            // somewhere between cargo 1.72 and 1.76 the behavior of "cargo add" changed
-            let mut buf = String::new();
-            file.read_to_string(&mut buf).unwrap();
-            if !buf.contains("[features]") {
-                file.write_all(
-                    include_str!("templates/Cargo.toml.feature-patch.template").as_bytes(),
-                )
-                .map_err(|_| Error::CreateFile("Cargo.toml".into()))?;
-            }
+            generated
+        };
 
-            file.write_all(
-                if softdevice.is_some() {
-                    include_str!("templates/Cargo.toml.sd.append").into()
-                } else {
-                    format!(
-                        include_str!("templates/Cargo.toml.append"),
-                        family = chip.family
-                    )
-                }
-                .as_bytes(),
-            )
-            .map_err(|_| Error::CreateFile("Cargo.toml".into()))?;
-        }
+        manifest.set_metadata(metadata)?;
 
-        Ok(())
+        self.create_file("Cargo.toml", &manifest.contents())
     }
 
     fn init_fmt(&self) -> Result<(), Error> {
@@ -340,7 +578,7 @@ impl Init {
 
     fn init_main(
         &self,
-        family: &Family,
+        chip: &Chip,
         panic_handler: &PanicHandler,
         softdevice: Option<&Softdevice>,
     ) -> Result<(), Error> {
@@ -348,7 +586,7 @@ impl Init {
 
         self.create_file(
             "src/main.rs",
-            &match (family, softdevice) {
+            &match (&chip.family, softdevice) {
                 (Family::STM, _) => format!(
                     include_str!("templates/main.rs.stm.template"),
                     panic_handler = panic_handler
@@ -359,33 +597,138 @@ impl Init {
                         panic_handler = panic_handler
                     )
                 }
+                (Family::NRF(_), None) if chip.is_nrf54l() => {
+                    format!(
+                        include_str!("templates/main.rs.nrf54l.template"),
+                        panic_handler = panic_handler
+                    )
+                }
                 (Family::NRF(_), None) => {
                     format!(
                         include_str!("templates/main.rs.nrf.template"),
                         panic_handler = panic_handler
                     )
                 }
-                (Family::ESP(_), _) => include_str!("templates/main.rs.esp.template").into(),
+                (Family::ESP(variant), _) => format!(
+                    include_str!("templates/main.rs.esp.template"),
+                    // the ESP32-H2 has no GPIO17
+                    led = match variant {
+                        Variant::H2 => "GPIO8",
+                        _ => "GPIO17",
+                    }
+                ),
+                (Family::RP(variant), _) => format!(
+                    include_str!("templates/main.rs.rp.template"),
+                    panic_handler = panic_handler,
+                    image_def = match variant {
+                        RpVariant::RP2040 => "",
+                        RpVariant::RP235xA | RpVariant::RP235xB => {
+                            include_str!("templates/main.rs.rp235x.image-def.template")
+                        }
+                    }
+                ),
             },
         )
     }
 
-    fn init_memory_x(&self, memory: MemRegion) -> Result<(), Error> {
+    fn init_memory_x(&self, memory: &Memory) -> Result<(), Error> {
+        let regions = [&memory.flash, &memory.ram]
+            .into_iter()
+            .chain(&memory.extra)
+            .map(|region| {
+                let length = match region.length % 1024 {
+                    0 => format!("{}K", region.length / 1024),
+                    _ => region.length.to_string(),
+                };
+
+                format!(
+                    "  {} : ORIGIN = 0x{:08x}, LENGTH = {length}\n",
+                    region.name, region.origin
+                )
+            })
+            .collect::<String>();
+
+        let sections = if memory.extra.is_empty() {
+            String::new()
+        } else {
+            format!(
+                include_str!("templates/memory.x.sections.template"),
+                sections = memory
+                    .extra
+                    .iter()
+                    .map(|region| format!(
+                        include_str!("templates/memory.x.section.template"),
+                        name = region.name,
+                        section = region.name.to_lowercase()
+                    ))
+                    .collect::<String>()
+            )
+        };
+
         self.create_file(
             "memory.x",
             &format!(
                 include_str!("templates/memory.x.template"),
-                flash_origin = memory.flash_origin,
-                flash_len = memory.flash_length,
-                ram_origin = memory.ram_origin,
-                ram_len = memory.ram_length,
+                regions = regions,
+                sections = sections
             ),
         )
     }
 
+    fn init_rp_memory_x(&self, variant: &RpVariant) -> Result<(), Error> {
+        self.create_file(
+            "memory.x",
+            match variant {
+                RpVariant::RP2040 => include_str!("templates/memory.x.rp2040.template"),
+                RpVariant::RP235xA | RpVariant::RP235xB => {
+                    include_str!("templates/memory.x.rp235x.template")
+                }
+            },
+        )
+    }
+
+    /// Prints a message above the progress bar, which draws nothing when hidden, e.g. in a dry
+    /// run or when not drawing to a terminal.
+    fn notice(&self, message: &str) {
+        if self.pb.is_hidden() {
+            eprintln!("{message}");
+        } else {
+            self.pb.println(message);
+        }
+    }
+
+    /// Creates a file in the workspace root, adding to an existing one without changing its
+    /// settings.
+    fn create_root_file(&self, name: &str, content: &str) -> Result<(), Error> {
+        if !Path::new(name).exists() {
+            return self.create_file(name, content);
+        }
+
+        let mut existing = Manifest::open(name)?;
+        existing.merge_missing(&Manifest::parse(name, content)?);
+
+        self.create_file(name, &existing.contents())
+    }
+
+    fn create_folder(&self, name: &str) -> Result<(), Error> {
+        if self.dry_run {
+            return Ok(());
+        }
+
+        self.transaction.borrow_mut().track(name);
+        fs::create_dir_all(name).map_err(|_| Error::CreateFolder(name.into()))
+    }
+
     fn create_file(&self, name: &str, content: &str) -> Result<(), Error> {
         self.pb.set_message(format!("Create file: {name}"));
 
+        if self.dry_run {
+            println!("==> {name} <==\n{}\n", content.trim_end());
+            return Ok(());
+        }
+
+        self.transaction.borrow_mut().track(name);
+
         let mut file = fs::OpenOptions::new()
             .write(true)
             .create(true)
@@ -398,32 +741,63 @@ impl Init {
 
         Ok(())
     }
+}
 
-    fn cargo_add(
-        &self,
-        name: &str,
-        features: Option<&[&str]>,
-        git: Option<&str>,
-        optional: bool,
-    ) -> Result<(), Error> {
-        self.pb.set_message(format!("Cargo add: {name}"));
-
-        let features = features.unwrap_or_default().join(",");
-        let mut cmd = Command::new("cargo");
-
-        cmd.arg("add")
-            .args([name, &format!("--features={features}")]);
-
-        if let Some(git) = git {
-            cmd.arg("--git").arg(git);
-        }
-
-        if optional {
-            cmd.arg("--optional");
-        }
+/// Runs cargo, failing with its message if it does not succeed.
+fn cargo(args: &[&str]) -> Result<(), Error> {
+    let command = format!("cargo {}", args.join(" "));
 
-        cmd.output().map_err(|_| Error::CargoAdd(name.into()))?;
+    let output = Command::new("cargo")
+        .args(args)
+        .output()
+        .map_err(|e| Error::CargoSpawn {
+            command: command.clone(),
+            message: e.to_string(),
+        })?;
 
+    if output.status.success() {
         Ok(())
+    } else {
+        Err(Error::CargoFailed {
+            command,
+            status: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().into(),
+        })
     }
 }
+
+/// The files written by `init` besides `Cargo.toml`, which is merged, and the VS Code config.
+const FILES: &[&str] = &[
+    "src/main.rs",
+    "src/fmt.rs",
+    "build.rs",
+    "memory.x",
+    "Embed.toml",
+    "rust-toolchain.toml",
+    ".cargo/config.toml",
+];
+
+/// The first existing file which `init` would overwrite, unless it was generated by cargo-embassy
+/// or is the `src/main.rs` from `cargo new`.
+fn custom_file(vscode: bool) -> Option<&'static str> {
+    let vscode = vscode.then_some(".vscode/launch.json");
+
+    FILES.iter().copied().chain(vscode).find(|name| {
+        fs::read_to_string(name).is_ok_and(|contents| match *name {
+            "src/main.rs" => contents.trim() != "fn main() {\n    println!(\"Hello, world!\");\n}",
+            _ => !contents
+                .lines()
+                .next()
+                .is_some_and(|line| line.ends_with("This file was automatically generated.")),
+        })
+    })
+}
+
+/// The name of the package in the current directory, or of the directory if there is none yet.
+fn package_name() -> String {
+    Manifest::open("Cargo.toml")
+        .ok()
+        .and_then(|manifest| manifest.package_name())
+        .or_else(|| current_dir().ok()?.file_name()?.to_str().map(String::from))
+        .unwrap_or_default()
+}
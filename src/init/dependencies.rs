@@ -0,0 +1,147 @@
+use crate::{
+    chip::{family::Family, Chip},
+    cli::init_args::{panic_handler::PanicHandler, soft_device::Softdevice},
+    manifest::{Dependency, Source},
+    registry, versions,
+};
+
+const SOFTDEVICE_GIT: &str = "https://github.com/embassy-rs/nrf-softdevice";
+
+/// The dependencies of a project for a chip, at the versions pinned for its family.
+pub fn dependencies(
+    chip: &Chip,
+    panic_handler: &PanicHandler,
+    softdevice: Option<&Softdevice>,
+) -> Vec<Dependency> {
+    let release = versions::release(&chip.family);
+    let mut dependencies = Vec::new();
+    let mut add = |name: &str, features: &[&str], optional: bool| {
+        let version = release
+            .get(name)
+            .unwrap_or_else(|| panic!("{name} has no pinned version"));
+
+        dependencies.push(Dependency {
+            name: name.into(),
+            source: Source::Version(version),
+            features: features.iter().map(|f| f.to_string()).collect(),
+            optional,
+        });
+    };
+
+    add(
+        "embassy-executor",
+        match &chip.family {
+            Family::ESP(_) => &["executor-thread"],
+            _ => &["arch-cortex-m", "executor-thread"],
+        },
+        false,
+    );
+    add("embassy-sync", &[], false);
+    add("embassy-futures", &[], false);
+    add(
+        "embassy-time",
+        match &chip.family {
+            // embassy-rp configures the tick rate of its time driver itself
+            Family::ESP(_) | Family::RP(_) => &[],
+            _ => &["tick-hz-32_768"],
+        },
+        false,
+    );
+
+    match &chip.family {
+        Family::STM => add(
+            "embassy-stm32",
+            &[
+                chip.name.as_str(),
+                "time-driver-any",
+                "exti",
+                "unstable-pac",
+            ],
+            false,
+        ),
+        Family::NRF(_) => add(
+            "embassy-nrf",
+            &[chip.name.as_str(), "gpiote", "time-driver-rtc1"],
+            false,
+        ),
+        Family::RP(_) => add(
+            "embassy-rp",
+            &[chip.name.as_str(), "time-driver", "critical-section-impl"],
+            false,
+        ),
+        Family::ESP(variant) => {
+            let name = variant.to_string();
+
+            add("embassy-time-driver", &[], false);
+            add(
+                "esp-backtrace",
+                &[&name, "exception-handler", "panic-handler", "println"],
+                false,
+            );
+            add("esp-hal", &[&name], false);
+            add("esp-hal-embassy", &[&name], false);
+            add("esp-println", &[&name, "log"], false);
+            add("log", &[], false);
+            add("static_cell", &[], false);
+        }
+    };
+
+    if !matches!(&chip.family, Family::ESP(_)) {
+        add(
+            "cortex-m",
+            // the softdevice and embassy-rp provide their own critical section implementation
+            if softdevice.is_some() || matches!(chip.family, Family::RP(_)) {
+                &["inline-asm"]
+            } else {
+                &["inline-asm", "critical-section-single-core"]
+            },
+            false,
+        );
+        add("cortex-m-rt", &[], false);
+        add("defmt", &[], true);
+        add("defmt-rtt", &[], true);
+        add("panic-probe", &["print-defmt"], true);
+        add(panic_handler.str(), &[], false);
+    }
+
+    if let Some(softdevice) = softdevice {
+        dependencies.push(Dependency {
+            name: "nrf-softdevice".into(),
+            source: Source::Git(SOFTDEVICE_GIT),
+            features: [
+                chip.name.as_str(),
+                softdevice.str(),
+                "ble-peripheral",
+                "ble-gatt-server",
+                "critical-section-impl",
+            ]
+            .map(String::from)
+            .into(),
+            optional: false,
+        });
+        dependencies.push(Dependency {
+            name: format!("nrf-softdevice-{}", softdevice.str()),
+            source: Source::Git(SOFTDEVICE_GIT),
+            features: Vec::new(),
+            optional: false,
+        });
+    }
+
+    dependencies
+}
+
+/// The dependencies which cannot be built offline, as `name@version` or `name@url`.
+pub fn uncached(dependencies: &[Dependency]) -> Vec<String> {
+    dependencies
+        .iter()
+        .filter_map(|dependency| match dependency.source {
+            Source::Version(version) if !registry::is_cached(&dependency.name, version) => {
+                Some(format!("{}@{version}", dependency.name))
+            }
+            Source::Git(url) if !registry::is_git_cached(url) => {
+                Some(format!("{}@{url}", dependency.name))
+            }
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,48 @@
+use std::{
+    fs,
+    path::{self, Path, PathBuf},
+};
+
+/// The files and folders written by `init`, so a failed run can be undone.
+#[derive(Default)]
+pub struct Transaction {
+    /// Files and folders which did not exist before, in the order they were created.
+    created: Vec<PathBuf>,
+    /// The original contents of overwritten files.
+    originals: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl Transaction {
+    /// Records a file or folder which is about to be written.
+    pub fn track(&mut self, path: impl AsRef<Path>) {
+        let Ok(path) = path::absolute(path) else {
+            return;
+        };
+
+        if self.created.contains(&path) || self.originals.iter().any(|(p, _)| *p == path) {
+            return;
+        }
+
+        match fs::read(&path) {
+            Ok(contents) => self.originals.push((path, contents)),
+            // existing folders are kept
+            Err(_) if path.exists() => {}
+            Err(_) => self.created.push(path),
+        }
+    }
+
+    /// Removes what was created and restores what was overwritten, as far as possible.
+    pub fn rollback(self) {
+        for path in self.created.iter().rev() {
+            let _ = if path.is_dir() {
+                fs::remove_dir_all(path)
+            } else {
+                fs::remove_file(path)
+            };
+        }
+
+        for (path, contents) in self.originals {
+            let _ = fs::write(path, contents);
+        }
+    }
+}
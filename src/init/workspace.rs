@@ -0,0 +1,37 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use toml_edit::DocumentMut;
+
+/// The cargo workspace a project is generated into with `--workspace`.
+pub struct Workspace {
+    root: PathBuf,
+    /// The project, relative to the workspace root, as listed in `workspace.members`.
+    pub member: String,
+}
+
+impl Workspace {
+    /// Finds the workspace enclosing the project at the absolute path `project`.
+    pub fn find(project: &Path) -> Option<Self> {
+        let root = project.ancestors().skip(1).find(|dir| is_root(dir))?;
+        let member = project.strip_prefix(root).ok()?;
+
+        Some(Self {
+            root: root.to_path_buf(),
+            member: member.to_str()?.replace('\\', "/"),
+        })
+    }
+
+    /// A path in the workspace root.
+    pub fn path(&self, name: &str) -> String {
+        self.root.join(name).display().to_string()
+    }
+}
+
+fn is_root(dir: &Path) -> bool {
+    fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|manifest| manifest.parse::<DocumentMut>().ok())
+        .is_some_and(|manifest| manifest.contains_key("workspace"))
+}
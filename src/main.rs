@@ -1,7 +1,12 @@
 pub mod chip;
+mod chips;
 pub mod cli;
 pub mod error;
+mod feature;
 mod init;
+mod manifest;
+mod registry;
+mod versions;
 
 use clap::Parser;
 use cli::{Cargo, Embassy, Feature};
@@ -12,14 +17,16 @@ fn main() {
 
     match embassy {
         Embassy::Init(args) => {
-            let init = Init::new();
+            let init = Init::new(args.dry_run);
             init.run(args);
         }
         Embassy::Docs => open::that("https://embassy.dev/book/dev/index.html")
             .expect("Failed to open Embassy documentation page."),
         Embassy::Feature(cmd) => match cmd {
-            Feature::List => termimad::print_text(include_str!("docs/features.md")),
-            Feature::Add => todo!(),
+            Feature::List(args) => feature::list(args),
+            Feature::Add(args) => feature::add(args),
+            Feature::Remove(args) => feature::remove(args),
         },
+        Embassy::Chips(args) => chips::list(args),
     }
 }
@@ -0,0 +1,525 @@
+use crate::error::Error;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Table, Value};
+
+/// Where a dependency comes from.
+pub(crate) enum Source {
+    Version(&'static str),
+    Git(&'static str),
+}
+
+/// A dependency of a generated project.
+pub(crate) struct Dependency {
+    pub name: String,
+    pub source: Source,
+    pub features: Vec<String>,
+    pub optional: bool,
+}
+
+/// The choices a project was generated with, kept in `[package.metadata.embassy]`.
+pub(crate) struct Metadata {
+    /// The chip as named by the HAL feature, e.g. `stm32g031k8`.
+    pub chip: String,
+    /// The probe-rs target, e.g. `STM32G031K8Tx`.
+    pub probe_target: String,
+    /// The chip family, as displayed by [`crate::chip::family::Family`].
+    pub family: String,
+    /// The target triple.
+    pub target: String,
+    /// The panic handler crate, unless the HAL brings its own.
+    pub panic_handler: Option<String>,
+    pub softdevice: Option<String>,
+    /// The version of cargo-embassy which generated the project.
+    pub generator_version: String,
+}
+
+/// A `Cargo.toml` (or another cargo TOML file, like `.cargo/config.toml`) that is edited in place,
+/// preserving formatting and comments.
+pub(crate) struct Manifest {
+    path: PathBuf,
+    doc: DocumentMut,
+}
+
+impl Manifest {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let display = path.display().to_string();
+
+        let contents = fs::read_to_string(&path).map_err(|_| Error::ReadFile(display))?;
+
+        Self::parse(path, &contents)
+    }
+
+    /// Parses a manifest which will be saved to `path`.
+    pub fn parse(path: impl AsRef<Path>, contents: &str) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let doc = contents
+            .parse()
+            .map_err(|_| Error::InvalidManifest(path.display().to_string()))?;
+
+        Ok(Self { path, doc })
+    }
+
+    pub fn contents(&self) -> String {
+        self.doc.to_string()
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        fs::write(&self.path, self.doc.to_string())
+            .map_err(|_| Error::CreateFile(self.path.display().to_string()))
+    }
+
+    /// Adds a dependency the way `cargo add` writes it, keeping `[dependencies]` sorted.
+    pub fn add_dependency(&mut self, dependency: &Dependency) -> Result<(), Error> {
+        let Dependency {
+            name,
+            source,
+            features,
+            optional,
+        } = dependency;
+
+        let deps = self
+            .doc
+            .entry("dependencies")
+            .or_insert(Item::Table(Table::new()))
+            .as_table_mut()
+            .ok_or(Error::InvalidManifest(self.path.display().to_string()))?;
+
+        let dep = match source {
+            Source::Version(version) if features.is_empty() && !optional => (*version).into(),
+            source => {
+                let mut table = InlineTable::new();
+                match source {
+                    Source::Version(version) => table.insert("version", (*version).into()),
+                    Source::Git(url) => table.insert("git", (*url).into()),
+                };
+                if !features.is_empty() {
+                    table.insert("features", Value::Array(features.iter().collect()));
+                }
+                if *optional {
+                    table.insert("optional", true.into());
+                }
+                table.fmt();
+
+                Value::InlineTable(table)
+            }
+        };
+
+        deps.insert(name, Item::Value(dep));
+        deps.sort_values();
+
+        Ok(())
+    }
+
+    /// Adds entries to a list of the `[features]` table, with one entry per line if there are
+    /// several.
+    pub fn add_feature(&mut self, name: &str, features: &[String]) -> Result<(), Error> {
+        let table = self
+            .doc
+            .entry("features")
+            .or_insert(Item::Table(Table::new()))
+            .as_table_mut()
+            .ok_or(Error::InvalidManifest(self.path.display().to_string()))?;
+
+        let mut entries: Vec<String> = table
+            .get(name)
+            .and_then(Item::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect();
+        for feature in features {
+            if !entries.contains(feature) {
+                entries.push(feature.clone());
+            }
+        }
+
+        let mut list: Array = entries.iter().collect();
+        if list.len() > 1 {
+            for feature in list.iter_mut() {
+                feature.decor_mut().set_prefix("\n    ");
+            }
+            list.set_trailing("\n");
+            list.set_trailing_comma(true);
+        }
+
+        table.insert(name, Item::Value(Value::Array(list)));
+
+        Ok(())
+    }
+
+    /// Merges a generated manifest into this one, keeping the package metadata and the existing
+    /// targets and profile settings.
+    ///
+    /// Returns the existing dependencies which were replaced by the generated ones as
+    /// `(name, existing, generated)`.
+    pub fn merge(&mut self, generated: &Manifest) -> Result<Vec<(String, String, String)>, Error> {
+        let mut replaced = Vec::new();
+
+        for (key, item) in generated.doc.iter() {
+            match key {
+                "package" => {}
+                "dependencies" => {
+                    let deps = self
+                        .doc
+                        .entry("dependencies")
+                        .or_insert(Item::Table(Table::new()))
+                        .as_table_like_mut()
+                        .ok_or(Error::InvalidManifest(self.path.display().to_string()))?;
+
+                    for (name, dep) in item.as_table_like().into_iter().flat_map(|t| t.iter()) {
+                        if let Some(existing) = deps.insert(name, dep.clone()) {
+                            let (existing, dep) = (display(existing), display(dep.clone()));
+                            if existing != dep {
+                                replaced.push((name.into(), existing, dep));
+                            }
+                        }
+                    }
+                    deps.sort_values();
+                }
+                "features" => {
+                    for (name, list) in item.as_table_like().into_iter().flat_map(|t| t.iter()) {
+                        let features: Vec<String> = list
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(Value::as_str)
+                            .map(String::from)
+                            .collect();
+
+                        self.add_feature(name, &features)?;
+                    }
+                }
+                // e.g. the settings of `[profile.dev]` which are not configured yet
+                _ => insert_missing(self.doc.as_table_mut(), key, item),
+            }
+        }
+
+        Ok(replaced)
+    }
+
+    /// Adds the keys of another file which are missing in this one, keeping existing values.
+    pub fn merge_missing(&mut self, other: &Manifest) {
+        for (key, item) in other.doc.iter() {
+            insert_missing(self.doc.as_table_mut(), key, item);
+        }
+    }
+
+    /// The toolchain channel of a `rust-toolchain.toml`.
+    pub fn toolchain_channel(&self) -> Option<&str> {
+        self.doc.get("toolchain")?.get("channel")?.as_str()
+    }
+
+    /// The targets installed by a `rust-toolchain.toml`.
+    pub fn toolchain_targets(&self) -> Vec<String> {
+        self.doc
+            .get("toolchain")
+            .and_then(|toolchain| toolchain.get("targets"))
+            .and_then(Item::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect()
+    }
+
+    /// Adds a target to `toolchain.targets` of a `rust-toolchain.toml`, unless it is listed.
+    pub fn add_toolchain_target(&mut self, target: &str) -> Result<(), Error> {
+        let targets = self
+            .doc
+            .entry("toolchain")
+            .or_insert(Item::Table(Table::new()))
+            .as_table_mut()
+            .and_then(|toolchain| {
+                toolchain
+                    .entry("targets")
+                    .or_insert(Item::Value(Value::Array(Array::new())))
+                    .as_array_mut()
+            })
+            .ok_or(Error::InvalidManifest(self.path.display().to_string()))?;
+
+        if !targets
+            .iter()
+            .any(|existing| existing.as_str() == Some(target))
+        {
+            targets.push(target);
+        }
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Item> {
+        self.doc.remove(key)
+    }
+
+    /// [`insert_missing`] at the document root, e.g. for `[profile]`.
+    pub fn insert_missing(&mut self, key: &str, item: &Item) {
+        insert_missing(self.doc.as_table_mut(), key, item);
+    }
+
+    /// Adds a member to `workspace.members`, unless it is already listed or matched by a glob.
+    pub fn add_workspace_member(&mut self, member: &str) -> Result<(), Error> {
+        let members = self
+            .doc
+            .get_mut("workspace")
+            .and_then(Item::as_table_like_mut)
+            .ok_or(Error::InvalidManifest(self.path.display().to_string()))?
+            .entry("members")
+            .or_insert(Item::Value(Value::Array(Array::new())))
+            .as_array_mut()
+            .ok_or(Error::InvalidManifest(self.path.display().to_string()))?;
+
+        let parent = member.rsplit_once('/').map_or("", |(parent, _)| parent);
+        let listed = members.iter().filter_map(Value::as_str).any(|listed| {
+            listed.trim_end_matches('/') == member
+                || listed.strip_suffix("/*") == Some(parent)
+                || (listed == "*" && parent.is_empty())
+        });
+
+        if !listed {
+            members.push(member);
+        }
+
+        Ok(())
+    }
+
+    /// The choices the project was generated with, if it was generated by cargo-embassy.
+    pub fn metadata(&self) -> Option<Metadata> {
+        let table = self.doc.get("package")?.get("metadata")?.get("embassy")?;
+        let get = |key: &str| table.get(key)?.as_str().map(String::from);
+
+        Some(Metadata {
+            chip: get("chip")?,
+            probe_target: get("probe-rs-target")?,
+            family: get("family")?,
+            target: get("target")?,
+            panic_handler: get("panic-handler"),
+            softdevice: get("softdevice"),
+            generator_version: get("generator-version")?,
+        })
+    }
+
+    /// Records the choices the project was generated with, replacing earlier ones.
+    pub fn set_metadata(&mut self, metadata: &Metadata) -> Result<(), Error> {
+        let invalid = || Error::InvalidManifest(self.path.display().to_string());
+
+        let package = self
+            .doc
+            .get_mut("package")
+            .and_then(Item::as_table_mut)
+            .ok_or_else(invalid)?;
+        let position = package.position();
+        let metadata_table = package
+            .entry("metadata")
+            .or_insert(Item::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(invalid)?;
+        metadata_table.set_implicit(true);
+
+        let mut table = Table::new();
+        table.insert("chip", toml_edit::value(&metadata.chip));
+        table.insert("probe-rs-target", toml_edit::value(&metadata.probe_target));
+        table.insert("family", toml_edit::value(&metadata.family));
+        table.insert("target", toml_edit::value(&metadata.target));
+        if let Some(panic_handler) = &metadata.panic_handler {
+            table.insert("panic-handler", toml_edit::value(panic_handler));
+        }
+        if let Some(softdevice) = &metadata.softdevice {
+            table.insert("softdevice", toml_edit::value(softdevice));
+        }
+        table.insert(
+            "generator-version",
+            toml_edit::value(&metadata.generator_version),
+        );
+        // right after `[package]`
+        if let Some(position) = position {
+            table.set_position(position);
+        }
+
+        metadata_table.insert("embassy", Item::Table(table));
+
+        Ok(())
+    }
+
+    /// The name of the package.
+    pub fn package_name(&self) -> Option<String> {
+        self.doc
+            .get("package")?
+            .get("name")?
+            .as_str()
+            .map(String::from)
+    }
+
+    /// The version requirement of a dependency, if it has one.
+    pub fn dependency_version(&self, name: &str) -> Option<String> {
+        let dep = self.doc.get("dependencies")?.get(name)?;
+
+        dep.as_str()
+            .or_else(|| dep.get("version")?.as_str())
+            .map(String::from)
+    }
+
+    /// The features enabled on a dependency, or `None` if it is not a dependency.
+    pub fn dependency_features(&self, name: &str) -> Option<Vec<String>> {
+        let dep = self.doc.get("dependencies")?.get(name)?;
+
+        Some(
+            dep.get("features")
+                .and_then(Item::as_array)
+                .map(|features| {
+                    features
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        )
+    }
+
+    /// The features of a dependency enabled directly or referenced from the `[features]` table,
+    /// or `None` if it is not a dependency.
+    pub fn enabled_features(&self, name: &str) -> Option<Vec<String>> {
+        let mut features = self.dependency_features(name)?;
+        for feature in self.feature_references(name) {
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+
+        Some(features)
+    }
+
+    /// Replaces the features enabled on an existing dependency.
+    pub fn set_dependency_features(
+        &mut self,
+        name: &str,
+        features: &[String],
+    ) -> Result<(), Error> {
+        let dep = self
+            .doc
+            .get_mut("dependencies")
+            .and_then(|deps| deps.get_mut(name))
+            .ok_or(Error::MissingDependency(name.into()))?;
+
+        // `name = "x.y.z"` must become `name = { version = "x.y.z", ... }` to hold features
+        if let Some(version) = dep.as_str() {
+            let mut table = InlineTable::new();
+            table.insert("version", version.into());
+            *dep = Item::Value(Value::InlineTable(table));
+        }
+
+        let table = dep
+            .as_table_like_mut()
+            .ok_or(Error::InvalidManifest(self.path.display().to_string()))?;
+
+        if features.is_empty() {
+            table.remove("features");
+        } else {
+            table.insert(
+                "features",
+                Item::Value(Value::Array(features.iter().collect::<Array>())),
+            );
+        }
+
+        if let Some(table) = dep.as_inline_table_mut() {
+            table.fmt();
+        }
+
+        Ok(())
+    }
+
+    /// The features of a dependency referenced from the `[features]` table, e.g. in `debug`.
+    pub fn feature_references(&self, name: &str) -> Vec<String> {
+        let Some(table) = self.doc.get("features").and_then(Item::as_table_like) else {
+            return Vec::new();
+        };
+
+        table
+            .iter()
+            .filter_map(|(_, list)| list.as_array())
+            .flat_map(|list| list.iter().filter_map(Value::as_str))
+            .filter_map(|reference| dependency_feature(reference, name))
+            .map(String::from)
+            .collect()
+    }
+
+    /// Removes references to features of a dependency from every list in the `[features]` table.
+    ///
+    /// Returns the removed references as `(list, reference)` pairs.
+    pub fn remove_feature_references(
+        &mut self,
+        name: &str,
+        features: &[String],
+    ) -> Vec<(String, String)> {
+        let mut removed = Vec::new();
+
+        let Some(table) = self
+            .doc
+            .get_mut("features")
+            .and_then(Item::as_table_like_mut)
+        else {
+            return removed;
+        };
+
+        for (list_name, list) in table.iter_mut() {
+            let Some(list) = list.as_array_mut() else {
+                continue;
+            };
+
+            list.retain(|reference| {
+                let Some(reference) = reference.as_str() else {
+                    return true;
+                };
+
+                let referenced = dependency_feature(reference, name)
+                    .is_some_and(|feature| features.iter().any(|f| f == feature));
+
+                if referenced {
+                    removed.push((list_name.to_string(), reference.to_string()));
+                }
+
+                !referenced
+            });
+        }
+
+        removed
+    }
+}
+
+/// Inserts an item if the key is missing, or its missing keys if both are tables.
+fn insert_missing(table: &mut Table, key: &str, item: &Item) {
+    match (table.get_mut(key), item.as_table()) {
+        (None, _) => {
+            table.insert(key, item.clone());
+        }
+        (Some(Item::Table(existing)), Some(item)) => {
+            for (key, item) in item.iter() {
+                insert_missing(existing, key, item);
+            }
+        }
+        (Some(_), _) => {}
+    }
+}
+
+/// Displays a dependency on one line, e.g. `{ version = "1.0.1", optional = true }`.
+fn display(dependency: Item) -> String {
+    match dependency.into_value() {
+        Ok(mut value) => {
+            value.decor_mut().clear();
+            value.to_string()
+        }
+        Err(item) => item.to_string().trim().into(),
+    }
+}
+
+/// Extracts `feature` from a `dep/feature` or `dep?/feature` reference to the dependency `name`.
+fn dependency_feature<'a>(reference: &'a str, name: &str) -> Option<&'a str> {
+    let (dep, feature) = reference.split_once('/')?;
+
+    (dep.strip_suffix('?').unwrap_or(dep) == name).then_some(feature)
+}
@@ -0,0 +1,200 @@
+use std::{fs, path::Path};
+use toml_edit::{DocumentMut, Item};
+
+/// An Embassy crate whose sources are in the local cargo registry cache.
+pub(crate) struct Crate {
+    pub name: String,
+    pub version: String,
+    pub features: Vec<String>,
+}
+
+/// Finds the `embassy-*` crates in the local cargo registry cache and reads their features.
+///
+/// When multiple versions of a crate are cached, the newest one satisfying `requirement` is used.
+pub(crate) fn discover(requirement: impl Fn(&str) -> Option<String>) -> Vec<Crate> {
+    let Ok(cargo_home) = home::cargo_home() else {
+        return Vec::new();
+    };
+    let Ok(indices) = fs::read_dir(cargo_home.join("registry").join("src")) else {
+        return Vec::new();
+    };
+
+    let mut newest: Vec<(String, Version, std::path::PathBuf)> = Vec::new();
+
+    for dir in indices
+        .flatten()
+        .filter_map(|index| fs::read_dir(index.path()).ok())
+        .flatten()
+        .flatten()
+    {
+        let file_name = dir.file_name();
+        let Some((name, version)) = file_name.to_str().and_then(split_name) else {
+            continue;
+        };
+
+        if !name.starts_with("embassy-") {
+            continue;
+        }
+
+        let Some(version) = Version::parse(version) else {
+            continue;
+        };
+
+        if requirement(name)
+            .and_then(|req| Version::parse(req.trim_start_matches(['^', '=', '~', ' '])))
+            .is_some_and(|req| !version.is_compatible_with(&req))
+        {
+            continue;
+        }
+
+        match newest.iter_mut().find(|(n, ..)| n == name) {
+            Some(entry) if entry.1 < version => *entry = (name.into(), version, dir.path()),
+            Some(_) => {}
+            None => newest.push((name.into(), version, dir.path())),
+        }
+    }
+
+    newest.sort_by(|a, b| a.0.cmp(&b.0));
+
+    newest
+        .into_iter()
+        .filter_map(|(name, version, path)| {
+            Some(Crate {
+                features: read_features(&path)?,
+                version: version.to_string(),
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Whether a crate version is in the local registry cache, either as a downloaded package or as
+/// extracted sources, so cargo can build it offline.
+pub(crate) fn is_cached(name: &str, version: &str) -> bool {
+    let Ok(cargo_home) = home::cargo_home() else {
+        return false;
+    };
+    let registry = cargo_home.join("registry");
+    let package = format!("{name}-{version}");
+
+    [("cache", format!("{package}.crate")), ("src", package)]
+        .iter()
+        .any(|(dir, entry)| {
+            fs::read_dir(registry.join(dir))
+                .into_iter()
+                .flatten()
+                .flatten()
+                .any(|index| index.path().join(entry).exists())
+        })
+}
+
+/// Whether a git repository has been fetched into the local cache, e.g. `nrf-softdevice` for
+/// `https://github.com/embassy-rs/nrf-softdevice`.
+pub(crate) fn is_git_cached(url: &str) -> bool {
+    let Ok(cargo_home) = home::cargo_home() else {
+        return false;
+    };
+    let repository = url.trim_end_matches('/').rsplit('/').next().unwrap_or(url);
+
+    fs::read_dir(cargo_home.join("git").join("db"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|db| db.file_name().into_string().ok())
+        // `<repository>-<hash>`
+        .any(|db| {
+            db.rsplit_once('-')
+                .is_some_and(|(name, _)| name == repository)
+        })
+}
+
+/// Splits a registry directory name like `embassy-time-0.3.2` into its name and version.
+fn split_name(dir: &str) -> Option<(&str, &str)> {
+    let index = dir
+        .char_indices()
+        .find(|(i, c)| *c == '-' && dir[i + 1..].starts_with(|c: char| c.is_ascii_digit()))?
+        .0;
+
+    Some((&dir[..index], &dir[index + 1..]))
+}
+
+fn read_features(path: &Path) -> Option<Vec<String>> {
+    let manifest: DocumentMut = fs::read_to_string(path.join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()?;
+
+    let declared = manifest.get("features").and_then(Item::as_table_like);
+    let mut features: Vec<String> = declared
+        .iter()
+        .flat_map(|table| table.iter())
+        .map(|(name, _)| name.to_string())
+        .collect();
+
+    // optional dependencies are implicit features unless referenced with `dep:`
+    let explicit = declared
+        .iter()
+        .flat_map(|table| table.iter())
+        .filter_map(|(_, list)| list.as_array())
+        .flatten()
+        .filter_map(|f| f.as_str()?.strip_prefix("dep:"))
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    if let Some(deps) = manifest.get("dependencies").and_then(Item::as_table_like) {
+        features.extend(
+            deps.iter()
+                .filter(|(name, dep)| {
+                    dep.get("optional").and_then(Item::as_bool) == Some(true)
+                        && !explicit.iter().any(|e| e == name)
+                })
+                .map(|(name, _)| name.to_string()),
+        );
+    }
+
+    features.retain(|f| f != "default" && !f.starts_with('_'));
+    features.sort();
+
+    Some(features)
+}
+
+#[derive(PartialEq, PartialOrd)]
+struct Version {
+    numbers: [u64; 3],
+    /// Release versions sort after pre-releases.
+    release: bool,
+    text: String,
+}
+
+impl Version {
+    fn parse(text: &str) -> Option<Self> {
+        let (numbers, pre) = match text.split_once(['-', '+']) {
+            Some((numbers, _)) => (numbers, true),
+            None => (text, false),
+        };
+
+        let mut parsed = [0; 3];
+        for (i, number) in numbers.split('.').enumerate() {
+            *parsed.get_mut(i)? = number.parse().ok()?;
+        }
+
+        Some(Self {
+            numbers: parsed,
+            release: !pre,
+            text: text.into(),
+        })
+    }
+
+    /// Whether this version satisfies the caret requirement `req`.
+    fn is_compatible_with(&self, req: &Self) -> bool {
+        let significant = req.numbers.iter().position(|n| *n != 0).unwrap_or(2);
+
+        self.numbers[..=significant] == req.numbers[..=significant] && self >= req
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
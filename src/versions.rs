@@ -0,0 +1,62 @@
+use crate::chip::family::Family;
+
+/// Crate versions which are tested together, so generated projects do not depend on what was
+/// most recently published.
+pub(crate) struct Release {
+    crates: &'static [(&'static str, &'static str)],
+}
+
+impl Release {
+    /// The pinned version of a crate, if it belongs to this release.
+    pub fn get(&self, krate: &str) -> Option<&'static str> {
+        self.crates
+            .iter()
+            .find(|(name, _)| *name == krate)
+            .map(|(_, version)| *version)
+    }
+}
+
+/// The release projects for a chip family are generated with.
+pub(crate) fn release(family: &Family) -> &'static Release {
+    match family {
+        Family::ESP(_) => &ESP,
+        Family::STM | Family::NRF(_) | Family::RP(_) => &EMBASSY,
+    }
+}
+
+/// The latest Embassy release, for the chips supported by the Embassy HALs.
+pub(crate) const EMBASSY: Release = Release {
+    crates: &[
+        ("embassy-executor", "0.9.1"),
+        ("embassy-sync", "0.7.2"),
+        ("embassy-futures", "0.1.2"),
+        ("embassy-time", "0.5.0"),
+        ("embassy-stm32", "0.4.0"),
+        ("embassy-nrf", "0.8.0"),
+        ("embassy-rp", "0.8.0"),
+        ("cortex-m", "0.7.7"),
+        ("cortex-m-rt", "0.7.5"),
+        ("defmt", "1.0.1"),
+        ("defmt-rtt", "1.0.0"),
+        ("panic-probe", "1.0.0"),
+        ("panic-halt", "1.0.0"),
+        ("panic-reset", "0.1.1"),
+    ],
+};
+
+/// The Embassy release `esp-hal` 0.22 is built against.
+pub(crate) const ESP: Release = Release {
+    crates: &[
+        ("embassy-executor", "0.6.3"),
+        ("embassy-sync", "0.6.1"),
+        ("embassy-futures", "0.1.1"),
+        ("embassy-time", "0.3.2"),
+        ("embassy-time-driver", "0.1.0"),
+        ("esp-hal", "0.22.0"),
+        ("esp-hal-embassy", "0.5.0"),
+        ("esp-backtrace", "0.14.2"),
+        ("esp-println", "0.12.0"),
+        ("log", "0.4.22"),
+        ("static_cell", "2.1.0"),
+    ],
+};